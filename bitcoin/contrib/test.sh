@@ -2,7 +2,7 @@
 
 set -ex
 
-FEATURES="base64 bitcoinconsensus serde rand secp-recovery"
+FEATURES="base64 bitcoinconsensus serde rand secp-recovery test-helpers"
 
 if [ "$DO_COV" = true ]
 then
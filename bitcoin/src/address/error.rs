@@ -152,3 +152,46 @@ impl From<witness_version::TryFromError> for ParseError {
 impl From<witness_program::Error> for ParseError {
     fn from(e: witness_program::Error) -> Self { Self::WitnessProgram(e) }
 }
+
+/// Error returned by [`verify_address_network`](crate::address::verify_address_network).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NetworkMismatchError {
+    /// The string is not a valid Lebowkis address.
+    Parse(ParseError),
+    /// The address is valid but for a different network.
+    Mismatch {
+        /// Network that was expected.
+        expected: Network,
+        /// Network the address was detected to belong to.
+        detected: Network,
+    },
+}
+
+impl fmt::Display for NetworkMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use NetworkMismatchError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "invalid address"; e),
+            Mismatch { expected, detected } =>
+                write!(f, "address belongs to network {}, expected {}", detected, expected),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NetworkMismatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use NetworkMismatchError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            Mismatch { .. } => None,
+        }
+    }
+}
+
+impl From<ParseError> for NetworkMismatchError {
+    fn from(e: ParseError) -> Self { Self::Parse(e) }
+}
@@ -52,7 +52,11 @@ use crate::taproot::TapNodeHash;
 
 /// Error code for the address module.
 pub mod error;
-pub use self::error::{Error, ParseError, UnknownAddressTypeError};
+pub use self::error::{Error, NetworkMismatchError, ParseError, UnknownAddressTypeError};
+
+/// Lebowkis payment URIs.
+pub mod uri;
+pub use self::uri::{PaymentUri, PaymentUriError};
 
 /// The different types of addresses.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -567,6 +571,38 @@ impl Address {
         Address::new(network, Payload::p2shwsh(script))
     }
 
+    /// Creates a P2SH-P2WSH address paying to `witness_script`.
+    ///
+    /// The P2WSH witness program `OP_0 <sha256(witness_script)>` is built and used as the P2SH
+    /// redeem script, going through the same size checks as [`Address::p2sh`]. The resulting
+    /// address is identical to [`Address::p2shwsh`].
+    pub fn p2sh_from_witness_script(
+        witness_script: &Script,
+        network: Network,
+    ) -> Result<Address, Error> {
+        Address::p2sh(&witness_script.to_p2wsh(), network)
+    }
+
+    /// Creates a segwit address from a witness version and program, e.g. as found in a script
+    /// pubkey.
+    ///
+    /// Version 0 programs must be 20 (P2WPKH) or 32 (P2WSH) bytes long and a 32 byte version 1
+    /// program is P2TR. Programs of any other version are valid (future) segwit addresses if they
+    /// are between 2 and 40 bytes long.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::WitnessProgram`] if the program length is invalid for `version`.
+    #[doc(alias = "witness_version_to_address")]
+    pub fn from_witness_program(
+        version: WitnessVersion,
+        program: &[u8],
+        network: Network,
+    ) -> Result<Address, Error> {
+        let program = WitnessProgram::new(version, program.to_vec())?;
+        Ok(Address::new(network, Payload::WitnessProgram(program)))
+    }
+
     /// Creates a pay to taproot address from an untweaked key.
     pub fn p2tr<C: Verification>(
         secp: &Secp256k1<C>,
@@ -612,6 +648,10 @@ impl Address {
     }
 
     /// Generates a script pubkey spending to this address.
+    ///
+    /// The returned script only uses minimal pushes (see [`Script::instructions_minimal`]) and is
+    /// never provably unspendable, for every address type. Also available as
+    /// [`Script::for_address`].
     pub fn script_pubkey(&self) -> ScriptBuf { self.payload().script_pubkey() }
 
     /// Creates a URI string *bitcoin:address* optimized to be encoded in QR codes.
@@ -674,6 +714,33 @@ impl Address {
     }
 }
 
+/// Random addresses for use in tests.
+#[cfg(feature = "test-helpers")]
+impl Address {
+    /// Creates a P2PKH address for a freshly generated random key.
+    pub fn random_p2pkh(network: Network, rng: &mut impl secp256k1::rand::RngCore) -> Address {
+        Address::p2pkh(&random_pubkey(rng), network)
+    }
+
+    /// Creates a P2WPKH address for a freshly generated random key.
+    pub fn random_p2wpkh(network: Network, rng: &mut impl secp256k1::rand::RngCore) -> Address {
+        Address::p2wpkh(&random_pubkey(rng), network).expect("random keys are compressed")
+    }
+
+    /// Creates a P2SH address paying to a P2PK script of a freshly generated random key.
+    pub fn random_p2sh(network: Network, rng: &mut impl secp256k1::rand::RngCore) -> Address {
+        let script = ScriptBuf::new_p2pk(&random_pubkey(rng));
+        Address::p2sh(&script, network).expect("P2PK scripts are small")
+    }
+}
+
+/// Generates a random compressed public key.
+#[cfg(feature = "test-helpers")]
+fn random_pubkey(rng: &mut impl secp256k1::rand::RngCore) -> PublicKey {
+    let secp = Secp256k1::signing_only();
+    PublicKey::new(secp256k1::SecretKey::new(rng).public_key(&secp))
+}
+
 /// Methods that can be called only on `Address<NetworkUnchecked>`.
 impl Address<NetworkUnchecked> {
     /// Returns a reference to the checked address.
@@ -681,6 +748,42 @@ impl Address<NetworkUnchecked> {
     pub fn assume_checked_ref(&self) -> &Address {
         unsafe { &*(self as *const Address<NetworkUnchecked> as *const Address) }
     }
+
+    /// Parses a legacy address from its base58check payload, the version byte followed by the
+    /// 20 byte hash, with the checksum already verified and removed.
+    ///
+    /// This is useful when the decoded bytes are at hand, e.g. from a binary protocol message, and
+    /// re-encoding them as a string just to parse them again can be avoided.
+    pub fn from_base58check_bytes(data: &[u8]) -> Result<Address<NetworkUnchecked>, ParseError> {
+        if data.len() != 21 {
+            return Err(ParseError::Base58(base58::Error::InvalidLength(data.len())));
+        }
+
+        let (network, address_type) = Address::network_from_version_byte(data[0])
+            .ok_or(ParseError::Base58(base58::Error::InvalidAddressVersion(data[0])))?;
+        let payload = match address_type {
+            AddressType::P2pkh => Payload::PubkeyHash(PubkeyHash::from_slice(&data[1..]).unwrap()),
+            _ => Payload::ScriptHash(ScriptHash::from_slice(&data[1..]).unwrap()),
+        };
+
+        Ok(Address::new(network, payload))
+    }
+
+    /// Returns the Lebowkis network and address type of a base58check version byte, or `None` if
+    /// no network uses `version`.
+    ///
+    /// Testnet and signet share their version bytes, these are returned as [`Network::Testnet`].
+    pub fn network_from_version_byte(version: u8) -> Option<(Network, AddressType)> {
+        match version {
+            PUBKEY_ADDRESS_PREFIX_MAIN => Some((Network::Bitcoin, AddressType::P2pkh)),
+            SCRIPT_ADDRESS_PREFIX_MAIN => Some((Network::Bitcoin, AddressType::P2sh)),
+            PUBKEY_ADDRESS_PREFIX_TEST => Some((Network::Testnet, AddressType::P2pkh)),
+            SCRIPT_ADDRESS_PREFIX_TEST => Some((Network::Testnet, AddressType::P2sh)),
+            PUBKEY_ADDRESS_PREFIX_REGTEST => Some((Network::Regtest, AddressType::P2pkh)),
+            SCRIPT_ADDRESS_PREFIX_REGTEST => Some((Network::Regtest, AddressType::P2sh)),
+            _ => None,
+        }
+    }
     /// Parsed addresses do not always have *one* network. The problem is that legacy testnet,
     /// regtest and signet addresse use the same prefix instead of multiple different ones. When
     /// parsing, such addresses are always assumed to be testnet addresses (the same is true for
@@ -839,27 +942,23 @@ impl FromStr for Address<NetworkUnchecked> {
             return Err(ParseError::Base58(base58::Error::InvalidLength(s.len() * 11 / 15)));
         }
         let data = base58::decode_check(s)?;
-        if data.len() != 21 {
-            return Err(ParseError::Base58(base58::Error::InvalidLength(data.len())));
-        }
-
-        let (network, payload) = match data[0] {
-            PUBKEY_ADDRESS_PREFIX_MAIN =>
-                (Network::Bitcoin, Payload::PubkeyHash(PubkeyHash::from_slice(&data[1..]).unwrap())),
-            SCRIPT_ADDRESS_PREFIX_MAIN =>
-                (Network::Bitcoin, Payload::ScriptHash(ScriptHash::from_slice(&data[1..]).unwrap())),
-            PUBKEY_ADDRESS_PREFIX_TEST =>
-                (Network::Testnet, Payload::PubkeyHash(PubkeyHash::from_slice(&data[1..]).unwrap())),
-            SCRIPT_ADDRESS_PREFIX_TEST =>
-                (Network::Testnet, Payload::ScriptHash(ScriptHash::from_slice(&data[1..]).unwrap())),
-            PUBKEY_ADDRESS_PREFIX_REGTEST =>
-                (Network::Regtest, Payload::PubkeyHash(PubkeyHash::from_slice(&data[1..]).unwrap())),
-            SCRIPT_ADDRESS_PREFIX_REGTEST =>
-                (Network::Regtest, Payload::ScriptHash(ScriptHash::from_slice(&data[1..]).unwrap())),
-            x => return Err(ParseError::Base58(base58::Error::InvalidAddressVersion(x))),
-        };
+        Address::from_base58check_bytes(&data)
+    }
+}
 
-        Ok(Address::new(network, payload))
+/// Parses `address` and checks that it is valid on the Lebowkis `network`.
+///
+/// This is a shorthand for parsing into an [`Address<NetworkUnchecked>`] followed by
+/// [`Address::require_network`], for validating user provided addresses.
+pub fn verify_address_network(
+    address: &str,
+    network: Network,
+) -> Result<Address, NetworkMismatchError> {
+    let address = address.parse::<Address<NetworkUnchecked>>()?;
+    if address.is_valid_for_network(network) {
+        Ok(address.assume_checked())
+    } else {
+        Err(NetworkMismatchError::Mismatch { expected: network, detected: *address.network() })
     }
 }
 
@@ -953,6 +1052,64 @@ mod tests {
         roundtrips(&addr);
     }
 
+    #[test]
+    fn test_network_from_version_byte() {
+        let known = [
+            (12, Bitcoin, AddressType::P2pkh),
+            (8, Bitcoin, AddressType::P2sh),
+            (13, Network::Testnet, AddressType::P2pkh),
+            (9, Network::Testnet, AddressType::P2sh),
+            (47, Network::Regtest, AddressType::P2pkh),
+            (5, Network::Regtest, AddressType::P2sh),
+        ];
+        for (version, network, address_type) in known.iter() {
+            assert_eq!(
+                Address::network_from_version_byte(*version),
+                Some((*network, *address_type))
+            );
+        }
+
+        // Bitcoin's mainnet P2PKH, testnet P2PKH and P2SH version bytes are not used. Bitcoin's
+        // mainnet P2SH version byte 5 is Lebowkis' regtest P2SH one.
+        for version in [0x00, 0x6f, 0xc4, 0xff].iter() {
+            assert_eq!(Address::network_from_version_byte(*version), None);
+        }
+    }
+
+    #[test]
+    fn test_from_base58check_bytes() {
+        let hash = hex!("162c5ea71c0b23f5b9022ef047c4a86470a5b070");
+        let cases = [
+            (PUBKEY_ADDRESS_PREFIX_MAIN, Bitcoin, AddressType::P2pkh),
+            (SCRIPT_ADDRESS_PREFIX_MAIN, Bitcoin, AddressType::P2sh),
+            (PUBKEY_ADDRESS_PREFIX_TEST, Network::Testnet, AddressType::P2pkh),
+            (SCRIPT_ADDRESS_PREFIX_TEST, Network::Testnet, AddressType::P2sh),
+            (PUBKEY_ADDRESS_PREFIX_REGTEST, Network::Regtest, AddressType::P2pkh),
+            (SCRIPT_ADDRESS_PREFIX_REGTEST, Network::Regtest, AddressType::P2sh),
+        ];
+        for (prefix, network, address_type) in cases.iter() {
+            let mut data = vec![*prefix];
+            data.extend_from_slice(&hash);
+
+            let addr = Address::from_base58check_bytes(&data).unwrap();
+            let addr = addr.require_network(*network).unwrap();
+            assert_eq!(addr.address_type(), Some(*address_type));
+            assert_eq!(addr.to_string(), base58::encode_check(&data));
+            assert_eq!(Address::from_str(&addr.to_string()).unwrap(), addr);
+        }
+
+        assert_eq!(
+            Address::from_base58check_bytes(&hash),
+            Err(ParseError::Base58(base58::Error::InvalidLength(20)))
+        );
+        let mut data = vec![0xff];
+        data.extend_from_slice(&hash);
+        assert_eq!(
+            Address::from_base58check_bytes(&data),
+            Err(ParseError::Base58(base58::Error::InvalidAddressVersion(0xff)))
+        );
+    }
+
     #[test]
     fn test_p2sh_address_58() {
         let addr = Address::new(
@@ -1013,6 +1170,39 @@ mod tests {
         roundtrips(&addr);
     }
 
+    #[test]
+    fn test_from_witness_program() {
+        use crate::blockdata::script::witness_program;
+
+        let key = "033bc8c83c52df5712229a2f72206d90192366c36428cb0c12b6af98324d97bfbc"
+            .parse::<PublicKey>()
+            .unwrap();
+        let wpubkey_hash = key.wpubkey_hash().unwrap();
+        let program = wpubkey_hash.as_byte_array();
+        let addr = Address::from_witness_program(WitnessVersion::V0, program, Bitcoin).unwrap();
+        assert_eq!(addr, Address::p2wpkh(&key, Bitcoin).unwrap());
+        assert_eq!(addr.address_type(), Some(AddressType::P2wpkh));
+
+        let script = ScriptBuf::new_op_return(&[0x01; 4]);
+        let wscript_hash = script.wscript_hash();
+        let program = wscript_hash.as_byte_array();
+        let addr = Address::from_witness_program(WitnessVersion::V0, program, Bitcoin).unwrap();
+        assert_eq!(addr, Address::p2wsh(&script, Bitcoin));
+        assert_eq!(addr.address_type(), Some(AddressType::P2wsh));
+
+        let addr = Address::from_witness_program(WitnessVersion::V1, &[0x02; 32], Bitcoin).unwrap();
+        assert_eq!(addr.address_type(), Some(AddressType::P2tr));
+
+        assert_eq!(
+            Address::from_witness_program(WitnessVersion::V0, &[0x02; 21], Bitcoin),
+            Err(Error::WitnessProgram(witness_program::Error::InvalidSegwitV0Length(21)))
+        );
+        assert_eq!(
+            Address::from_witness_program(WitnessVersion::V1, &[0x02; 41], Bitcoin),
+            Err(Error::WitnessProgram(witness_program::Error::InvalidLength(41)))
+        );
+    }
+
     #[test]
     fn test_p2shwpkh() {
         // stolen from Bitcoin transaction: ad3fd9c6b52e752ba21425435ff3dd361d6ac271531fc1d2144843a9f550ad01
@@ -1039,6 +1229,18 @@ mod tests {
         roundtrips(&addr);
     }
 
+    #[test]
+    fn test_p2sh_from_witness_script() {
+        let script = ScriptBuf::from_hex("522103e5529d8eaa3d559903adb2e881eb06c86ac2574ffa503c45f4e942e2a693b33e2102e5f10fcdcdbab211e0af6a481f5532536ec61a5fdbf7183770cf8680fe729d8152ae").unwrap();
+        let addr = Address::p2sh_from_witness_script(&script, Bitcoin).unwrap();
+        // Lebowkis mainnet P2SH addresses (version byte 8) start with '4'.
+        assert!(addr.to_string().starts_with('4'));
+        assert_eq!(addr.address_type(), Some(AddressType::P2sh));
+        assert_eq!(addr, Address::p2shwsh(&script, Bitcoin));
+        assert_eq!(addr.script_pubkey(), ScriptBuf::new_p2sh(&script.to_p2wsh().script_hash()));
+        roundtrips(&addr);
+    }
+
     #[test]
     fn test_non_existent_segwit_version() {
         // 40-byte program
@@ -1458,4 +1660,90 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_verify_address_network() {
+        let key = "048d5141948c1702e8c95f438815794b87f706a8d4cd2bffad1dc1570971032c9b6042a0431ded2478b5c9cf2d81c124a5e57347a3c63ef0e7716cf54d613ba183"
+            .parse::<PublicKey>()
+            .unwrap();
+        let mainnet = Address::p2pkh(&key, Bitcoin).to_string();
+        let testnet = Address::p2pkh(&key, Testnet).to_string();
+
+        let addr = verify_address_network(&mainnet, Bitcoin).unwrap();
+        assert_eq!(addr, Address::p2pkh(&key, Bitcoin));
+        assert!(verify_address_network(&testnet, Testnet).is_ok());
+        assert_eq!(
+            verify_address_network(&mainnet, Testnet),
+            Err(NetworkMismatchError::Mismatch { expected: Testnet, detected: Bitcoin })
+        );
+        assert_eq!(
+            verify_address_network(&testnet, Bitcoin),
+            Err(NetworkMismatchError::Mismatch { expected: Bitcoin, detected: Testnet })
+        );
+        // A Bitcoin mainnet address does not use a Lebowkis version byte.
+        assert!(matches!(
+            verify_address_network("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Bitcoin),
+            Err(NetworkMismatchError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn script_pubkey_minimal() {
+        use hashes::{sha256, Hash};
+
+        use crate::network::Network::{Regtest, Signet};
+
+        for i in 0u32..64 {
+            let seed = sha256::Hash::hash(&i.to_le_bytes()).to_byte_array();
+            let payloads = [
+                Payload::PubkeyHash(PubkeyHash::from_slice(&seed[..20]).unwrap()),
+                Payload::ScriptHash(ScriptHash::from_slice(&seed[12..]).unwrap()),
+                Payload::WitnessProgram(
+                    WitnessProgram::new(WitnessVersion::V0, seed[..20].to_vec()).unwrap(),
+                ),
+                Payload::WitnessProgram(WitnessProgram::new(WitnessVersion::V0, seed).unwrap()),
+                Payload::WitnessProgram(WitnessProgram::new(WitnessVersion::V1, seed).unwrap()),
+            ];
+            for payload in payloads.iter() {
+                for network in [Bitcoin, Testnet, Signet, Regtest].iter() {
+                    let addr = Address::new(*network, payload.clone());
+                    let script = Script::for_address(&addr);
+                    assert_eq!(script, addr.script_pubkey());
+                    assert!(
+                        script.instructions_minimal().all(|ins| ins.is_ok()),
+                        "non-minimal script for {}",
+                        addr
+                    );
+                    assert!(!script.is_provably_unspendable(), "unspendable script for {}", addr);
+                    assert_eq!(Address::from_script(&script, *network).as_ref(), Ok(&addr));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "test-helpers")]
+    fn random_addresses() {
+        use std::collections::HashSet;
+
+        use secp256k1::rand::rngs::mock::StepRng;
+
+        use crate::network::Network::{Regtest, Signet};
+
+        // A counter is enough to get distinct keys and does not need `rand-std`.
+        let mut rng = StepRng::new(1, 1);
+        let mut seen = HashSet::new();
+        for i in 0..100 {
+            let network = [Bitcoin, Testnet, Signet, Regtest][i % 4];
+            let addr = match i % 3 {
+                0 => Address::random_p2pkh(network, &mut rng),
+                1 => Address::random_p2wpkh(network, &mut rng),
+                _ => Address::random_p2sh(network, &mut rng),
+            };
+            let parsed = addr.to_string().parse::<Address<NetworkUnchecked>>().unwrap();
+            assert_eq!(parsed.require_network(network).unwrap(), addr);
+            assert!(seen.insert(addr));
+        }
+        assert_eq!(seen.len(), 100);
+    }
 }
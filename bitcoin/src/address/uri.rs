@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Lebowkis payment URIs.
+//!
+//! Payment URIs follow [BIP21] using the `lbw:` scheme instead of `bitcoin:`, for example
+//! `lbw:ADDRESS?amount=19.98&label=Donation`. Amounts are in whole coins.
+//!
+//! [BIP21]: <https://github.com/bitcoin/bips/blob/master/bip-0021.mediawiki>
+//!
+
+use core::fmt;
+use core::str::FromStr;
+
+use internals::write_err;
+
+use super::{Address, NetworkUnchecked, NetworkValidation, ParseError};
+use crate::amount::{Amount, Denomination, ParseAmountError};
+use crate::prelude::*;
+
+/// The URI scheme of Lebowkis payment URIs.
+pub const PAYMENT_URI_SCHEME: &str = "lbw";
+
+/// A parsed Lebowkis payment URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    /// The address to pay to.
+    pub address: Address<NetworkUnchecked>,
+    /// The amount requested.
+    pub amount: Option<Amount>,
+    /// A label for the address, e.g. the name of the recipient.
+    pub label: Option<String>,
+    /// A message describing the payment.
+    pub message: Option<String>,
+}
+
+impl fmt::Display for PaymentUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", PAYMENT_URI_SCHEME)?;
+        self.address.fmt_internal(f)?;
+
+        let mut separator = '?';
+        if let Some(amount) = self.amount {
+            write!(f, "{}amount={}", separator, amount.to_string_in(Denomination::Bitcoin))?;
+            separator = '&';
+        }
+        if let Some(ref label) = self.label {
+            write!(f, "{}label=", separator)?;
+            percent_encode(f, label)?;
+            separator = '&';
+        }
+        if let Some(ref message) = self.message {
+            write!(f, "{}message=", separator)?;
+            percent_encode(f, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentUri {
+    type Err = PaymentUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = match s.find(':') {
+            Some(pos) if s[..pos].eq_ignore_ascii_case(PAYMENT_URI_SCHEME) => &s[pos + 1..],
+            _ => return Err(PaymentUriError::InvalidScheme),
+        };
+        let (address, query) = match rest.find('?') {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+            None => (rest, None),
+        };
+
+        let mut uri = PaymentUri {
+            address: address.parse().map_err(PaymentUriError::Address)?,
+            amount: None,
+            label: None,
+            message: None,
+        };
+        for param in query.into_iter().flat_map(|query| query.split('&')) {
+            let (key, value) = match param.find('=') {
+                Some(pos) => (&param[..pos], &param[pos + 1..]),
+                None => (param, ""),
+            };
+            let duplicate = match key {
+                "amount" => uri
+                    .amount
+                    .replace(
+                        Amount::from_str_in(value, Denomination::Bitcoin)
+                            .map_err(PaymentUriError::Amount)?,
+                    )
+                    .is_some(),
+                "label" => uri.label.replace(percent_decode(value)?).is_some(),
+                "message" => uri.message.replace(percent_decode(value)?).is_some(),
+                // Unknown parameters must be rejected only if they are required.
+                _ if key.starts_with("req-") =>
+                    return Err(PaymentUriError::UnknownRequiredParameter(key.to_owned())),
+                _ => false,
+            };
+            if duplicate {
+                return Err(PaymentUriError::DuplicateParameter(key.to_owned()));
+            }
+        }
+        Ok(uri)
+    }
+}
+
+impl<V: NetworkValidation> Address<V> {
+    /// Creates a Lebowkis payment URI (`lbw:ADDRESS?amount=...`) paying to this address.
+    ///
+    /// The label and message are percent-encoded, see [`PaymentUri`] for parsing.
+    pub fn to_payment_uri(
+        &self,
+        amount: Option<Amount>,
+        label: Option<&str>,
+        message: Option<&str>,
+    ) -> String {
+        PaymentUri {
+            address: self.as_unchecked().clone(),
+            amount,
+            label: label.map(ToOwned::to_owned),
+            message: message.map(ToOwned::to_owned),
+        }
+        .to_string()
+    }
+}
+
+/// Writes `s` percent-encoding everything but unreserved characters (RFC 3986).
+fn percent_encode(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' =>
+                write!(f, "{}", byte as char)?,
+            _ => write!(f, "%{:02X}", byte)?,
+        }
+    }
+    Ok(())
+}
+
+/// Decodes a percent-encoded UTF-8 string.
+fn percent_decode(s: &str) -> Result<String, PaymentUriError> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            // `from_str_radix` alone would accept a sign, as in "%+1".
+            let hex = s
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .ok_or(PaymentUriError::InvalidPercentEncoding)?;
+            let byte = u8::from_str_radix(hex, 16).expect("two hex digits always fit in a u8");
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| PaymentUriError::InvalidPercentEncoding)
+}
+
+/// An error parsing a [`PaymentUri`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PaymentUriError {
+    /// The URI does not start with the `lbw:` scheme.
+    InvalidScheme,
+    /// The address is invalid.
+    Address(ParseError),
+    /// The amount is invalid.
+    Amount(ParseAmountError),
+    /// A label or message is not valid percent-encoded UTF-8.
+    InvalidPercentEncoding,
+    /// A parameter prefixed with `req-` is not understood.
+    UnknownRequiredParameter(String),
+    /// A parameter appears more than once.
+    DuplicateParameter(String),
+}
+
+impl fmt::Display for PaymentUriError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PaymentUriError::*;
+
+        match *self {
+            InvalidScheme => write!(f, "payment URI does not start with {}:", PAYMENT_URI_SCHEME),
+            Address(ref e) => write_err!(f, "invalid address in payment URI"; e),
+            Amount(ref e) => write_err!(f, "invalid amount in payment URI"; e),
+            InvalidPercentEncoding => f.write_str("invalid percent-encoding in payment URI"),
+            UnknownRequiredParameter(ref key) =>
+                write!(f, "unknown required parameter {} in payment URI", key),
+            DuplicateParameter(ref key) => write!(f, "duplicate parameter {} in payment URI", key),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PaymentUriError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use PaymentUriError::*;
+
+        match *self {
+            Address(ref e) => Some(e),
+            Amount(ref e) => Some(e),
+            InvalidScheme
+            | InvalidPercentEncoding
+            | UnknownRequiredParameter(_)
+            | DuplicateParameter(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::key::PublicKey;
+    use crate::network::Network;
+
+    fn mainnet_address() -> Address {
+        let key = "048d5141948c1702e8c95f438815794b87f706a8d4cd2bffad1dc1570971032c9b6042a0431ded2478b5c9cf2d81c124a5e57347a3c63ef0e7716cf54d613ba183"
+            .parse::<PublicKey>()
+            .unwrap();
+        Address::p2pkh(&key, Network::Bitcoin)
+    }
+
+    #[test]
+    fn payment_uri_roundtrip() {
+        let address = mainnet_address();
+        let amount = Amount::from_sat(1_998_000_000);
+        let uri = address.to_payment_uri(Some(amount), Some("Lebowski's rug"), None);
+        assert_eq!(uri, format!("lbw:{}?amount=19.98&label=Lebowski%27s%20rug", address));
+
+        let parsed = uri.parse::<PaymentUri>().unwrap();
+        assert_eq!(parsed.address, *address.as_unchecked());
+        assert_eq!(parsed.amount, Some(amount));
+        assert_eq!(parsed.label.as_deref(), Some("Lebowski's rug"));
+        assert_eq!(parsed.message, None);
+        assert_eq!(parsed.to_string(), uri);
+
+        let uri = address.to_payment_uri(None, None, Some("caf\u{e9} & more"));
+        assert_eq!(uri, format!("lbw:{}?message=caf%C3%A9%20%26%20more", address));
+        assert_eq!(uri.parse::<PaymentUri>().unwrap().message.as_deref(), Some("caf\u{e9} & more"));
+
+        assert_eq!(address.to_payment_uri(None, None, None), format!("lbw:{}", address));
+    }
+
+    #[test]
+    fn payment_uri_errors() {
+        let address = mainnet_address();
+        let parse = |s: String| s.parse::<PaymentUri>();
+
+        assert_eq!(parse(format!("bitcoin:{}", address)), Err(PaymentUriError::InvalidScheme));
+        assert!(parse(format!("LBW:{}?foo=bar", address)).is_ok());
+        assert!(matches!(parse("lbw:notanaddress".to_owned()), Err(PaymentUriError::Address(_))));
+        assert!(matches!(
+            parse(format!("lbw:{}?amount=abc", address)),
+            Err(PaymentUriError::Amount(_))
+        ));
+        assert_eq!(
+            parse(format!("lbw:{}?label=%FF", address)),
+            Err(PaymentUriError::InvalidPercentEncoding)
+        );
+        for label in ["%+1", "%-1", "%1", "%G0"].iter() {
+            assert_eq!(
+                parse(format!("lbw:{}?label={}", address, label)),
+                Err(PaymentUriError::InvalidPercentEncoding)
+            );
+        }
+        assert_eq!(
+            parse(format!("lbw:{}?req-foo=1", address)),
+            Err(PaymentUriError::UnknownRequiredParameter("req-foo".to_owned()))
+        );
+        assert_eq!(
+            parse(format!("lbw:{}?amount=1&amount=2", address)),
+            Err(PaymentUriError::DuplicateParameter("amount".to_owned()))
+        );
+    }
+}
@@ -53,6 +53,12 @@ pub enum Denomination {
     Satoshi,
     /// msat
     MilliSatoshi,
+    /// LBW, the Lebowkis unit, equal to one BTC
+    Lbw,
+    /// mLBW
+    MilliLbw,
+    /// uLBW
+    MicroLbw,
 }
 
 impl Denomination {
@@ -68,6 +74,9 @@ impl Denomination {
             Denomination::Bit => -2,
             Denomination::Satoshi => 0,
             Denomination::MilliSatoshi => 3,
+            Denomination::Lbw => -8,
+            Denomination::MilliLbw => -5,
+            Denomination::MicroLbw => -2,
         }
     }
 
@@ -83,6 +92,9 @@ impl Denomination {
             Denomination::Bit => "bits",
             Denomination::Satoshi => "satoshi",
             Denomination::MilliSatoshi => "msat",
+            Denomination::Lbw => "LBW",
+            Denomination::MilliLbw => "mLBW",
+            Denomination::MicroLbw => "uLBW",
         }
     }
 
@@ -99,6 +111,9 @@ impl Denomination {
             "SATOSHI" | "satoshi" | "SATOSHIS" | "satoshis" | "SAT" | "sat" | "SATS" | "sats" =>
                 Some(Denomination::Satoshi),
             "mSAT" | "msat" | "mSATs" | "msats" => Some(Denomination::MilliSatoshi),
+            "LBW" | "lbw" => Some(Denomination::Lbw),
+            "mLBW" | "mlbw" => Some(Denomination::MilliLbw),
+            "uLBW" | "ulbw" | "\u{3bc}LBW" => Some(Denomination::MicroLbw),
             _ => None,
         }
     }
@@ -106,8 +121,8 @@ impl Denomination {
 
 /// These form are ambigous and could have many meanings.  For example, M could denote Mega or Milli.
 /// If any of these forms are used, an error type PossiblyConfusingDenomination is returned.
-const CONFUSING_FORMS: [&str; 9] =
-    ["Msat", "Msats", "MSAT", "MSATS", "MSat", "MSats", "MBTC", "Mbtc", "PBTC"];
+const CONFUSING_FORMS: [&str; 11] =
+    ["Msat", "Msats", "MSAT", "MSATS", "MSat", "MSats", "MBTC", "Mbtc", "PBTC", "MLBW", "Mlbw"];
 
 impl fmt::Display for Denomination {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(self.as_str()) }
@@ -119,7 +134,7 @@ impl FromStr for Denomination {
     /// Convert from a str to Denomination.
     ///
     /// Any combination of upper and/or lower case, excluding uppercase of SI(m, u, n, p) is considered valid.
-    /// - Singular: BTC, mBTC, uBTC, nBTC, pBTC
+    /// - Singular: BTC, mBTC, uBTC, nBTC, pBTC, LBW, mLBW, uLBW
     /// - Plural or singular: sat, satoshi, bit, msat
     ///
     /// Due to ambiguity between mega and milli, pico and peta we prohibit usage of leading capital 'M', 'P'.
@@ -201,6 +216,25 @@ impl std::error::Error for ParseAmountError {
     }
 }
 
+/// Error returned by [`Amount::from_sat_checked`] when the amount exceeds [`Amount::MAX_MONEY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExceedsMaxMoneyError {
+    /// The out of range number of satoshis.
+    pub sat: u64,
+}
+
+impl fmt::Display for ExceedsMaxMoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} satoshis exceeds the maximum amount of money", self.sat)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExceedsMaxMoneyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+}
+
 fn is_too_precise(s: &str, precision: usize) -> bool {
     match s.find('.') {
         Some(pos) =>
@@ -516,9 +550,28 @@ impl Amount {
     /// Create an [Amount] with satoshi precision and the given number of satoshis.
     pub const fn from_sat(satoshi: u64) -> Amount { Amount(satoshi) }
 
+    /// Create an [Amount] with the given number of satoshis, checking that it does not exceed
+    /// [`Amount::MAX_MONEY`].
+    ///
+    /// Use this where the amount represents a real output value rather than e.g. a sum.
+    pub fn from_sat_checked(satoshi: u64) -> Result<Amount, ExceedsMaxMoneyError> {
+        if satoshi > Amount::MAX_MONEY.0 {
+            Err(ExceedsMaxMoneyError { sat: satoshi })
+        } else {
+            Ok(Amount(satoshi))
+        }
+    }
+
     /// Gets the number of satoshis in this [`Amount`].
+    #[doc(alias = "as_sat")]
     pub fn to_sat(self) -> u64 { self.0 }
 
+    /// Returns the amount as 8 little-endian bytes, as it is serialized in a transaction output.
+    pub const fn to_le_bytes(self) -> [u8; 8] { self.0.to_le_bytes() }
+
+    /// Creates an [Amount] from 8 little-endian bytes, as it is serialized in a transaction output.
+    pub const fn from_le_bytes(bytes: [u8; 8]) -> Amount { Amount(u64::from_le_bytes(bytes)) }
+
     /// The maximum value of an [Amount].
     #[deprecated(since = "0.31.0", note = "Use Self::MAX instead")]
     pub const fn max_value() -> Amount { Amount(u64::max_value()) }
@@ -632,6 +685,13 @@ impl Amount {
         }
     }
 
+    /// Create an object that implements [`fmt::Display`] showing all the decimal places of
+    /// `denomination` followed by the denomination, e.g. `19.98000000 LBW`.
+    #[doc(alias = "format_in_denomination")]
+    pub fn to_display(self, denomination: Denomination) -> DisplayAmount {
+        DisplayAmount { amount: self, denomination }
+    }
+
     /// Format the value of this [Amount] in the given denomination.
     ///
     /// Does not include the denomination.
@@ -686,6 +746,15 @@ impl Amount {
     /// Returns [None] if overflow occurred.
     pub fn checked_rem(self, rhs: u64) -> Option<Amount> { self.0.checked_rem(rhs).map(Amount) }
 
+    /// Checked integer division, rounding up.
+    ///
+    /// Useful to never underpay, e.g. when splitting a fee. Returns [None] if `rhs` is zero.
+    pub fn div_ceil(self, rhs: u64) -> Option<Amount> {
+        let quotient = self.0.checked_div(rhs)?;
+        // `rhs` is not zero here, a remainder means the quotient was rounded down.
+        Some(Amount(if self.0 % rhs == 0 { quotient } else { quotient + 1 }))
+    }
+
     /// Convert to a signed amount.
     pub fn to_signed(self) -> Result<SignedAmount, ParseAmountError> {
         if self.to_sat() > SignedAmount::MAX.to_sat() as u64 {
@@ -853,6 +922,29 @@ impl fmt::Display for Display {
     }
 }
 
+/// A helper/builder that displays an [`Amount`] with every decimal place of its denomination.
+///
+/// Unlike [`Display`] trailing zeros are never stripped and the denomination is always shown. A
+/// precision given to the formatter is ignored.
+///
+/// See [`Amount::to_display`] on how to construct this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayAmount {
+    amount: Amount,
+    denomination: Denomination,
+}
+
+impl fmt::Display for DisplayAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = self.denomination.precision().min(0);
+        let options = FormatOptions {
+            precision: Some(usize::from(unsigned_abs(decimals))),
+            ..FormatOptions::from_formatter(f)
+        };
+        fmt_satoshi_in(self.amount.to_sat(), false, f, self.denomination, true, options)
+    }
+}
+
 #[derive(Clone, Debug)]
 enum DisplayStyle {
     FixedDenomination { denomination: Denomination, show_denomination: bool },
@@ -1624,6 +1716,24 @@ mod tests {
     #[test]
     fn from_int_btc_panic() { Amount::from_int_btc(u64::MAX); }
 
+    #[test]
+    fn le_bytes() {
+        let amount = Amount::from_sat(1_998_000_000);
+        assert_eq!(amount.to_le_bytes(), [0x80, 0x0f, 0x17, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(Amount::from_le_bytes(amount.to_le_bytes()), amount);
+        assert_eq!(crate::consensus::encode::serialize(&amount), amount.to_le_bytes());
+        assert_eq!(Amount::from_le_bytes(Amount::MAX.to_le_bytes()), Amount::MAX);
+    }
+
+    #[test]
+    fn from_sat_checked() {
+        let max = Amount::MAX_MONEY.to_sat();
+        assert_eq!(Amount::from_sat_checked(0), Ok(Amount::ZERO));
+        assert_eq!(Amount::from_sat_checked(max), Ok(Amount::MAX_MONEY));
+        assert_eq!(Amount::from_sat_checked(max + 1), Err(ExceedsMaxMoneyError { sat: max + 1 }));
+        assert_eq!(Amount::from_sat_checked(u64::MAX), Err(ExceedsMaxMoneyError { sat: u64::MAX }));
+    }
+
     #[test]
     fn mul_div() {
         let sat = Amount::from_sat;
@@ -1665,6 +1775,19 @@ mod tests {
 
         assert_eq!(sat(5).checked_div(2), Some(sat(2))); // integer division
         assert_eq!(ssat(-6).checked_div(2), Some(ssat(-3)));
+
+        assert_eq!(sat(100).checked_mul(3), Some(sat(300)));
+        assert_eq!(Amount::MAX.checked_mul(2), None);
+        assert_eq!(sat(1).checked_mul(u64::MAX), Some(Amount::MAX));
+        assert_eq!(sat(300).checked_div(3), Some(sat(100)));
+        assert_eq!(sat(300).checked_div(0), None);
+
+        assert_eq!(sat(300).div_ceil(3), Some(sat(100)));
+        assert_eq!(sat(301).div_ceil(3), Some(sat(101)));
+        assert_eq!(sat(0).div_ceil(3), Some(sat(0)));
+        assert_eq!(Amount::MAX.div_ceil(2), Some(sat(u64::MAX / 2 + 1)));
+        assert_eq!(Amount::MAX.div_ceil(1), Some(Amount::MAX));
+        assert_eq!(sat(1).div_ceil(0), None);
     }
 
     #[test]
@@ -2350,6 +2473,28 @@ mod tests {
         assert_eq!(Some(SignedAmount::from_sat(3364)), sum);
     }
 
+    #[test]
+    fn to_display() {
+        let amount = Amount::from_sat(1_998_000_000);
+        assert_eq!(amount.to_display(Denomination::Lbw).to_string(), "19.98000000 LBW");
+        assert_eq!(amount.to_display(Denomination::MilliLbw).to_string(), "19980.00000 mLBW");
+        assert_eq!(amount.to_display(Denomination::MicroLbw).to_string(), "19980000.00 uLBW");
+        assert_eq!(amount.to_display(Denomination::Satoshi).to_string(), "1998000000 satoshi");
+        assert_eq!(amount.to_display(Denomination::Bitcoin).to_string(), "19.98000000 BTC");
+        assert_eq!(format!("{:>20}", amount.to_display(Denomination::Lbw)), "     19.98000000 LBW");
+
+        assert_eq!(Amount::ZERO.to_display(Denomination::Lbw).to_string(), "0.00000000 LBW");
+        let one = Amount::from_sat(1);
+        assert_eq!(one.to_display(Denomination::MilliLbw).to_string(), "0.00001 mLBW");
+
+        for denom in [Denomination::Lbw, Denomination::MilliLbw, Denomination::MicroLbw].iter() {
+            let s = amount.to_display(*denom).to_string();
+            assert_eq!(Amount::from_str(&s), Ok(amount));
+            assert_eq!(Denomination::from_str(&denom.to_string()), Ok(*denom));
+        }
+        assert_eq!(Denomination::from_str("\u{3bc}LBW"), Ok(Denomination::MicroLbw));
+    }
+
     #[test]
     fn denomination_string_acceptable_forms() {
         // Non-exhaustive list of valid forms.
@@ -145,6 +145,7 @@ impl Decodable for ShortId {
 /// transactions IDs used for matching already-available transactions, and a
 /// select few transactions which we expect a peer may be missing.
 #[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Hash)]
+#[doc(alias = "CompactBlock")]
 pub struct HeaderAndShortIds {
     /// The header of the block being provided.
     pub header: block::Header,
@@ -431,6 +432,28 @@ mod test {
         assert_eq!(idxs, vec![0, 1]);
     }
 
+    #[test]
+    fn test_genesis_short_ids() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase = genesis.txdata[0].txid().to_raw_hash();
+
+        // The coinbase is always prefilled so the genesis block has no short IDs.
+        let compact = HeaderAndShortIds::from_block(&genesis, 0, 1, &[]).unwrap();
+        assert!(compact.short_ids.is_empty());
+        assert_eq!(compact.prefilled_txs[0].tx, genesis.txdata[0]);
+
+        let keys = ShortId::calculate_siphash_keys(&genesis.header, 0);
+        let id = ShortId::with_siphash_keys(&coinbase, keys);
+        assert_eq!(id, ShortId([0x6c, 0xe8, 0xca, 0x71, 0xd8, 0xc6]));
+
+        let keys = ShortId::calculate_siphash_keys(&genesis.header, 42);
+        let id = ShortId::with_siphash_keys(&coinbase, keys);
+        assert_eq!(id, ShortId([0x68, 0x49, 0xd7, 0xae, 0x8d, 0xf2]));
+    }
+
     #[test]
     fn test_compact_block_vector() {
         // Tested with Elements implementation of compact blocks.
@@ -8,16 +8,17 @@
 //! these blocks and the blockchain.
 //!
 
+use core::convert::TryFrom;
 use core::fmt;
 
 use hashes::{Hash, HashEngine};
 
 use super::Weight;
 use crate::blockdata::script;
-use crate::blockdata::transaction::Transaction;
+use crate::blockdata::transaction::{OutPoint, Transaction};
 use crate::consensus::{encode, Decodable, Encodable};
 pub use crate::hash_types::BlockHash;
-use crate::hash_types::{TxMerkleNode, WitnessCommitment, WitnessMerkleNode, Wtxid};
+use crate::hash_types::{TxMerkleNode, Txid, WitnessCommitment, WitnessMerkleNode, Wtxid};
 use crate::internal_macros::impl_consensus_encoding;
 use crate::pow::{CompactTarget, Target, Work};
 use crate::prelude::*;
@@ -50,6 +51,12 @@ pub struct Header {
     /// The nonce, selected to obtain a low enough blockhash.
     pub nonce: u32,
 
+    /// The merge mining proof, present exactly when [`Header::is_auxpow`] is `true`.
+    ///
+    /// Consensus encoding an auxpow header without it fails, so [`serialize`] panics on such a
+    /// header. Decoding never produces one.
+    ///
+    /// [`serialize`]: crate::consensus::encode::serialize
     pub aux_data: Option<AuxPow>,
 }
 
@@ -59,7 +66,7 @@ impl Decodable for Header {
         reader: &mut R,
     ) -> Result<Self, encode::Error> {
         let base = SimpleHeader::consensus_decode_from_finite_reader(reader)?;
-        if (base.version.0 & 0x100) == 0 {
+        if !base.version.is_auxpow() {
             return Ok(Header {
                 version: base.version,
                 prev_blockhash: base.prev_blockhash,
@@ -87,7 +94,7 @@ impl Decodable for Header {
         use crate::io::Read as _;
         let mut r = reader.take(encode::MAX_VEC_SIZE as u64);
         let thing = SimpleHeader::consensus_decode(r.by_ref())?;
-        if (thing.version.0 & 0x100) == 0 {
+        if !thing.version.is_auxpow() {
             return Ok(Header {
                 version: thing.version,
                 prev_blockhash: thing.prev_blockhash,
@@ -119,8 +126,11 @@ impl Encodable for Header {
         len += self.time.consensus_encode(writer)?;
         len += self.bits.consensus_encode(writer)?;
         len += self.nonce.consensus_encode(writer)?;
-        if (self.version.0 & 0x100) != 0 {
-            len += self.aux_data.as_ref().unwrap().consensus_encode(writer)?;
+        if self.is_auxpow() {
+            let aux_data = self.aux_data.as_ref().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "auxpow header without aux_data")
+            })?;
+            len += aux_data.consensus_encode(writer)?;
         }
         Ok(len)
     }
@@ -166,6 +176,14 @@ impl Header {
         BlockHash::from_engine(engine)
     }
 
+    /// Returns the double SHA256 of the 80 byte header, excluding any auxpow data.
+    ///
+    /// This is the same as [`Header::block_hash`] and is what [`Header::validate_pow`] checks
+    /// against the target. Note that the Lebowkis proof-of-work algorithm is not implemented
+    /// here: the genesis header does not meet its own target under either double SHA256 or
+    /// scrypt, so `validate_pow` should not be relied upon for Lebowkis headers.
+    pub fn hash_legacy(&self) -> BlockHash { self.block_hash() }
+
     /// Computes the target (range [0, T] inclusive) that a blockhash must land in to be valid.
     pub fn target(&self) -> Target {
         self.bits.into()
@@ -195,9 +213,130 @@ impl Header {
         }
     }
 
+    /// Sets `bits` and increments the nonce until the block hash meets the target, returning
+    /// the nonce found.
+    ///
+    /// This is a brute force search meant for regtest and tests, where the difficulty is trivially
+    /// low. It never returns if no nonce meets the target.
+    #[cfg(feature = "test-helpers")]
+    pub fn mine_regtest(&mut self, bits: CompactTarget) -> u32 {
+        self.bits = bits;
+        let target = self.target();
+        while !target.is_met_by(self.block_hash()) {
+            self.nonce = self.nonce.wrapping_add(1);
+        }
+        self.nonce
+    }
+
+    /// Returns `true` if this is the header of a merge-mined block.
+    ///
+    /// AuxPoW headers are consensus encoded with their [`aux_data`](Header::aux_data) after the
+    /// 80 byte base header, which must be present.
+    pub fn is_auxpow(&self) -> bool { self.version.is_auxpow() }
+
     /// Returns the total work of the block.
-    pub fn work(&self) -> Work {
-        self.target().to_work()
+    pub fn work(&self) -> Work { self.target().to_work() }
+
+    /// Returns the total work of a sequence of headers, e.g. a chain or one of its branches.
+    ///
+    /// Saturates at [`Work::MAX`] rather than overflowing.
+    pub fn total_chain_work(headers: &[Header]) -> Work {
+        headers
+            .iter()
+            .try_fold(Work::ZERO, |total, header| {
+                total.checked_add(Work::from_compact_target(header.bits))
+            })
+            .unwrap_or(Work::MAX)
+    }
+
+    /// Computes the target of the difficulty period following the one from `first_header` to
+    /// `last_header`.
+    ///
+    /// This applies the retarget formula (see [`Target::retarget`]) to the target of
+    /// `last_header` and the time elapsed between the two headers. Callers are responsible for
+    /// passing the headers at the boundaries of the [`DIFFCHANGE_INTERVAL`] window.
+    ///
+    /// [`DIFFCHANGE_INTERVAL`]: crate::blockdata::constants::DIFFCHANGE_INTERVAL
+    pub fn compute_next_target(first_header: &Header, last_header: &Header) -> CompactTarget {
+        let actual_timespan = last_header.time.saturating_sub(first_header.time);
+        last_header.target().retarget(u64::from(actual_timespan)).to_compact_lossy()
+    }
+
+    /// Validates a chain of consecutive headers, returning their block hashes.
+    ///
+    /// `headers[0]` is expected at `start_height` with target `genesis_bits` (the genesis target
+    /// when starting from height 0). Each following header must:
+    ///
+    /// * commit to the hash of the header before it,
+    /// * keep the target of the header before it, except at multiples of [`DIFFCHANGE_INTERVAL`]
+    ///   where it must match [`Header::compute_next_target`] for the previous period,
+    /// * have a timestamp greater than the median of the (up to) 11 headers before it.
+    ///
+    /// Every header must also pass `check_pow`, which is given the header and returns whether its
+    /// proof of work is valid. Passing `|h| h.validate_pow(h.target()).is_ok()` checks the hash
+    /// of the header itself, see [`Header::hash_legacy`] for its limitations; merge-mined headers
+    /// need a check of the parent block in their [`AuxPow`] data instead.
+    ///
+    /// Retargets whose previous period starts before `start_height` can not be checked and the
+    /// target found in the header is accepted. Minimum difficulty blocks as allowed on the test
+    /// networks are not supported. Headers are validated sequentially, the proof of work checks
+    /// are not run in parallel.
+    ///
+    /// [`DIFFCHANGE_INTERVAL`]: crate::blockdata::constants::DIFFCHANGE_INTERVAL
+    pub fn batch_validate<F>(
+        headers: &[Header],
+        start_height: u32,
+        genesis_bits: CompactTarget,
+        check_pow: F,
+    ) -> Result<Vec<BlockHash>, HeaderValidationError>
+    where
+        F: Fn(&Header) -> bool,
+    {
+        use HeaderValidationError::*;
+
+        use crate::blockdata::constants::DIFFCHANGE_INTERVAL;
+
+        let mut hashes: Vec<BlockHash> = Vec::with_capacity(headers.len());
+        for (i, header) in headers.iter().enumerate() {
+            let height = u32::try_from(i)
+                .ok()
+                .and_then(|i| start_height.checked_add(i))
+                .ok_or(HeightOverflow { index: i })?;
+
+            let expected_bits = match i.checked_sub(1).map(|prev| &headers[prev]) {
+                None => genesis_bits,
+                Some(prev) => {
+                    if header.prev_blockhash != hashes[i - 1] {
+                        return Err(BadPrevBlockhash { height });
+                    }
+
+                    let mut times: Vec<u32> =
+                        headers[i.saturating_sub(11)..i].iter().map(|h| h.time).collect();
+                    times.sort_unstable();
+                    if header.time <= times[times.len() / 2] {
+                        return Err(TimeTooOld { height });
+                    }
+
+                    if height % DIFFCHANGE_INTERVAL != 0 {
+                        prev.bits
+                    } else if height - start_height >= DIFFCHANGE_INTERVAL {
+                        let first = &headers[i - DIFFCHANGE_INTERVAL as usize];
+                        Header::compute_next_target(first, prev)
+                    } else {
+                        header.bits
+                    }
+                }
+            };
+            if header.bits != expected_bits {
+                return Err(BadTarget { height, expected: expected_bits, got: header.bits });
+            }
+
+            if !check_pow(header) {
+                return Err(BadProofOfWork { height });
+            }
+            hashes.push(header.block_hash());
+        }
+        Ok(hashes)
     }
     pub fn get_size(&self) -> usize {
         /*if self.aux_data.is_none() {
@@ -315,18 +454,44 @@ impl Version {
     /// The value has the top three bits `001` which enables the use of version bits to signal for soft forks.
     const USE_VERSION_BITS: u32 = 0x2000_0000;
 
+    /// Version bit set by merge-mined blocks, whose header is followed by [`AuxPow`] data.
+    const AUXPOW_FLAG: i32 = 0x100;
+
+    /// The smallest valid AuxPoW chain ID.
+    pub const AUXPOW_CHAIN_ID_MIN: u16 = 1;
+
+    /// The largest valid AuxPoW chain ID, the sign bit of the version is never part of it.
+    pub const AUXPOW_CHAIN_ID_MAX: u16 = 0x7FFF;
+
     /// Creates a [`Version`] from a signed 32 bit integer value.
     ///
     /// This is the data type used in consensus code in Bitcoin Core.
-    pub fn from_consensus(v: i32) -> Self {
-        Version(v)
-    }
+    pub fn from_consensus(v: i32) -> Self { Version(v) }
 
     /// Returns the inner `i32` value.
     ///
     /// This is the data type used in consensus code in Bitcoin Core.
-    pub fn to_consensus(self) -> i32 {
-        self.0
+    pub fn to_consensus(self) -> i32 { self.0 }
+
+    /// Returns `true` if the version marks a merge-mined (AuxPoW) block.
+    pub fn is_auxpow(&self) -> bool { self.0 & Self::AUXPOW_FLAG != 0 }
+
+    /// Returns the AuxPoW chain ID, stored in the upper 16 bits of the version.
+    pub fn chain_id(&self) -> u16 { (self.0 as u32 >> 16) as u16 }
+
+    /// Checks that the version carries the `expected` AuxPoW chain ID.
+    ///
+    /// Merge-mined blocks commit to the chain ID of the chain they are mined for so that the same
+    /// parent block work can not be used twice on one chain.
+    pub fn validate_chain_id(self, expected: u16) -> Result<(), ChainIdError> {
+        let chain_id = self.chain_id();
+        if !(Self::AUXPOW_CHAIN_ID_MIN..=Self::AUXPOW_CHAIN_ID_MAX).contains(&chain_id) {
+            return Err(ChainIdError::OutOfRange(chain_id));
+        }
+        if chain_id != expected {
+            return Err(ChainIdError::Mismatch { actual: chain_id, expected });
+        }
+        Ok(())
     }
 
     /// Checks whether the version number is signalling a soft fork at the given bit.
@@ -396,6 +561,10 @@ impl Block {
         self.header.block_hash()
     }
 
+    /// Returns the work represented by this block's difficulty target, used to compare the
+    /// accumulated work of competing chains.
+    pub fn chain_work(&self) -> Work { Work::from_compact_target(self.header.bits) }
+
     /// Checks if merkle root of header matches merkle root of the transaction list.
     pub fn check_merkle_root(&self) -> bool {
         match self.compute_merkle_root() {
@@ -509,8 +678,24 @@ impl Block {
         size
     }
 
+    /// Returns the size of the block in bytes, including witness data.
+    ///
+    /// This is the length of the block's consensus serialization, computed from the transaction
+    /// sizes rather than by serializing the block. Same as [`Block::total_size`].
+    pub fn size(&self) -> usize {
+        self.total_size()
+    }
+
+    /// Returns the size of the block in bytes with all witness data stripped.
+    ///
+    /// This is the size a non-upgraded node sees, which is also the base size used to compute
+    /// [`Block::weight`].
+    pub fn stripped_size(&self) -> usize {
+        self.base_size()
+    }
+
     /// Returns the stripped size of the block.
-    #[deprecated(since = "0.31.0", note = "use Block::base_size() instead")]
+    #[deprecated(since = "0.31.0", note = "use Block::stripped_size() instead")]
     pub fn strippedsize(&self) -> usize {
         self.base_size()
     }
@@ -520,6 +705,74 @@ impl Block {
         self.txdata.first()
     }
 
+    /// Returns the scriptSig of the coinbase transaction, if one is present.
+    pub fn coinbase_script_sig(&self) -> Option<&script::Script> {
+        self.coinbase().and_then(|cb| cb.input.first()).map(|input| input.script_sig.as_script())
+    }
+
+    /// Returns the message embedded by the miner in the coinbase, i.e. the data of the last push
+    /// in the coinbase scriptSig.
+    ///
+    /// Returns `None` if there is no coinbase scriptSig or it does not end with a data push.
+    pub fn coinbase_message(&self) -> Option<Vec<u8>> {
+        let mut message = None;
+        for instruction in self.coinbase_script_sig()?.instructions() {
+            message = match instruction.ok()? {
+                script::Instruction::PushBytes(bytes) => Some(bytes.as_bytes().to_vec()),
+                script::Instruction::Op(_) => None,
+            };
+        }
+        message
+    }
+
+    /// Returns the transaction with the given `txid`, if it is in the block.
+    ///
+    /// This hashes the transactions of the block until a match is found, to look up many
+    /// transactions build an index once with [`Block::build_txid_index`].
+    pub fn get_transaction(&self, txid: &Txid) -> Option<&Transaction> {
+        self.txdata.iter().find(|tx| tx.txid() == *txid)
+    }
+
+    /// Returns a map from the txid of each transaction in the block to its index in `txdata`.
+    #[cfg(feature = "std")]
+    pub fn build_txid_index(&self) -> std::collections::HashMap<Txid, usize> {
+        self.txdata.iter().enumerate().map(|(index, tx)| (tx.txid(), index)).collect()
+    }
+
+    /// Returns the `(tx_index, input_index)` of each input in the block that spends `outpoint`.
+    ///
+    /// A valid block spends an outpoint at most once, more than one result is a double spend.
+    /// Coinbase inputs are skipped.
+    pub fn transactions_spending_outpoint(&self, outpoint: &OutPoint) -> Vec<(usize, usize)> {
+        self.all_spent_outpoints()
+            .into_iter()
+            .filter(|(spent, _, _)| spent == outpoint)
+            .map(|(_, tx_index, input_index)| (tx_index, input_index))
+            .collect()
+    }
+
+    /// Returns every outpoint spent by the block, with the `(tx_index, input_index)` spending it.
+    ///
+    /// Outpoints are in block order, coinbase inputs are skipped.
+    pub fn all_spent_outpoints(&self) -> Vec<(OutPoint, usize, usize)> {
+        self.txdata
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| !tx.is_coinbase())
+            .flat_map(|(tx_index, tx)| {
+                tx.input
+                    .iter()
+                    .enumerate()
+                    .map(move |(input_index, input)| (input.previous_output, tx_index, input_index))
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the transactions of the block along with their txids.
+    pub fn transactions_with_index(&self) -> impl Iterator<Item = (Txid, &Transaction)> {
+        self.txdata.iter().map(|tx| (tx.txid(), tx))
+    }
+
     /// Returns the block height, as encoded in the coinbase transaction according to BIP34.
     pub fn bip34_block_height(&self) -> Result<u64, Bip34Error> {
         // Citing the spec:
@@ -552,6 +805,32 @@ impl Block {
             _ => Err(Bip34Error::NotPresent),
         }
     }
+
+    /// Checks that the coinbase transaction commits to `height` as required by BIP34.
+    ///
+    /// The coinbase scriptSig must start with the minimal push of `height`, which is the same
+    /// check Lebowkis Core performs. BIP34 is enforced from [`Params::bip34_height`] (height 1 on
+    /// all Lebowkis networks) so every block except genesis is expected to pass.
+    ///
+    /// [`Params::bip34_height`]: crate::consensus::Params::bip34_height
+    pub fn validate_coinbase_bip34(&self, height: u32) -> Result<(), CoinbaseError> {
+        let cb = self.coinbase().filter(|tx| tx.is_coinbase()).ok_or(CoinbaseError::NotCoinbase)?;
+        let script_sig = &cb.input[0].script_sig;
+
+        let expected = script::Builder::new().push_int(height.into()).into_script();
+        if script_sig.as_bytes().starts_with(expected.as_bytes()) {
+            return Ok(());
+        }
+
+        let actual = match script_sig.instructions().next() {
+            Some(Ok(instruction)) => instruction.script_num(),
+            _ => None,
+        };
+        match actual {
+            Some(actual) => Err(CoinbaseError::WrongHeight { actual, expected: height }),
+            None => Err(CoinbaseError::MissingHeight),
+        }
+    }
 }
 
 impl From<Header> for BlockHash {
@@ -618,6 +897,86 @@ impl std::error::Error for Bip34Error {
     }
 }
 
+/// An error validating the BIP34 height of a coinbase transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CoinbaseError {
+    /// The first transaction of the block is missing or not a coinbase.
+    NotCoinbase,
+    /// The coinbase scriptSig does not start with a number (pre-BIP34 format).
+    MissingHeight,
+    /// The coinbase commits to a different height.
+    WrongHeight {
+        /// Height found in the coinbase scriptSig.
+        actual: i64,
+        /// Height of the block.
+        expected: u32,
+    },
+}
+
+impl fmt::Display for CoinbaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CoinbaseError::*;
+
+        match *self {
+            NotCoinbase => write!(f, "first transaction of the block is not a coinbase"),
+            MissingHeight => write!(f, "coinbase does not commit to a BIP34 height"),
+            WrongHeight { actual, expected } =>
+                write!(f, "coinbase commits to height {} but block is at {}", actual, expected),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoinbaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use CoinbaseError::*;
+
+        match *self {
+            NotCoinbase | MissingHeight | WrongHeight { .. } => None,
+        }
+    }
+}
+
+/// An error validating the AuxPoW chain ID of a block version, see [`Version::validate_chain_id`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChainIdError {
+    /// The chain ID is not within [`Version::AUXPOW_CHAIN_ID_MIN`] and
+    /// [`Version::AUXPOW_CHAIN_ID_MAX`].
+    OutOfRange(u16),
+    /// The chain ID is not the expected one.
+    Mismatch {
+        /// Chain ID found in the version.
+        actual: u16,
+        /// Chain ID of the chain the block is validated for.
+        expected: u16,
+    },
+}
+
+impl fmt::Display for ChainIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ChainIdError::*;
+
+        match *self {
+            OutOfRange(id) => write!(f, "AuxPoW chain ID {:#06x} is out of range", id),
+            Mismatch { actual, expected } =>
+                write!(f, "AuxPoW chain ID {:#06x} does not match {:#06x}", actual, expected),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChainIdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ChainIdError::*;
+
+        match *self {
+            OutOfRange(_) | Mismatch { .. } => None,
+        }
+    }
+}
+
 /// A block validation error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -650,6 +1009,80 @@ impl std::error::Error for ValidationError {
     }
 }
 
+/// An error validating a chain of headers, see [`Header::batch_validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HeaderValidationError {
+    /// The header at `height` does not commit to the hash of the header before it.
+    BadPrevBlockhash {
+        /// The height of the offending header.
+        height: u32,
+    },
+    /// The header at `height` does not have the expected target.
+    BadTarget {
+        /// The height of the offending header.
+        height: u32,
+        /// The target the header should have.
+        expected: CompactTarget,
+        /// The target found in the header.
+        got: CompactTarget,
+    },
+    /// The hash of the header at `height` does not meet its target.
+    BadProofOfWork {
+        /// The height of the offending header.
+        height: u32,
+    },
+    /// The timestamp of the header at `height` is not greater than the median time past.
+    TimeTooOld {
+        /// The height of the offending header.
+        height: u32,
+    },
+    /// The height of the header at `index` in the batch does not fit in a `u32`.
+    HeightOverflow {
+        /// The index of the offending header in the batch.
+        index: usize,
+    },
+}
+
+impl fmt::Display for HeaderValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use HeaderValidationError::*;
+
+        match *self {
+            BadPrevBlockhash { height } =>
+                write!(f, "header at height {} does not connect to the previous one", height),
+            BadTarget { height, expected, got } => write!(
+                f,
+                "header at height {} has target {:#010x}, expected {:#010x}",
+                height,
+                got.to_consensus(),
+                expected.to_consensus()
+            ),
+            BadProofOfWork { height } =>
+                write!(f, "header at height {} does not meet its target", height),
+            TimeTooOld { height } =>
+                write!(f, "header at height {} is not newer than the median time past", height),
+            HeightOverflow { index } =>
+                write!(f, "height of the header at index {} overflows a u32", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use HeaderValidationError::*;
+
+        match *self {
+            BadPrevBlockhash { .. }
+            | BadTarget { .. }
+            | BadProofOfWork { .. }
+            | TimeTooOld { .. }
+            | HeightOverflow { .. } => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hex::{test_hex_unwrap as hex, FromHex};
@@ -676,6 +1109,383 @@ mod tests {
         assert_eq!(bad.bip34_block_height(), Err(super::Bip34Error::UnexpectedPush(push)));
     }
 
+    #[test]
+    fn genesis_block_size() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase_size = serialize(&genesis.txdata[0]).len();
+        // Header, one byte for the transaction count and the coinbase itself.
+        assert_eq!(genesis.size(), 80 + 1 + coinbase_size);
+        assert_eq!(genesis.size(), serialize(&genesis).len());
+        // Without witness data both sizes agree.
+        assert_eq!(genesis.stripped_size(), genesis.size());
+        assert_eq!(
+            genesis.weight(),
+            Weight::from_wu_usize(genesis.stripped_size() * 3 + genesis.size())
+        );
+    }
+
+    #[test]
+    fn coinbase_message() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::blockdata::opcodes::all::OP_CHECKSIG;
+        use crate::network::Network;
+
+        let mut genesis = genesis_block(Network::Bitcoin);
+        assert_eq!(
+            genesis.coinbase_script_sig(),
+            Some(genesis.txdata[0].input[0].script_sig.as_script())
+        );
+        assert_eq!(
+            genesis.coinbase_message().unwrap(),
+            b"Six Flags coaster victim concerned about seat. USAToday - 07.20.2013".to_vec()
+        );
+
+        genesis.txdata[0].input[0].script_sig =
+            script::Builder::new().push_int(1).push_opcode(OP_CHECKSIG).into_script();
+        assert_eq!(genesis.coinbase_message(), None);
+
+        genesis.txdata.clear();
+        assert_eq!(genesis.coinbase_script_sig(), None);
+        assert_eq!(genesis.coinbase_message(), None);
+    }
+
+    #[test]
+    fn spent_outpoints() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::blockdata::transaction::{TxIn, TxOut};
+        use crate::network::Network;
+
+        let mut block = genesis_block(Network::Bitcoin);
+        let coinbase = OutPoint::new(block.txdata[0].txid(), 0);
+        let unknown = OutPoint::new(Txid::all_zeros(), 3);
+        let spend = |outpoints: &[OutPoint]| Transaction {
+            version: crate::blockdata::transaction::Version::TWO,
+            lock_time: crate::absolute::LockTime::ZERO,
+            input: outpoints
+                .iter()
+                .map(|outpoint| TxIn { previous_output: *outpoint, ..Default::default() })
+                .collect(),
+            output: vec![TxOut::NULL],
+        };
+        let first = spend(&[coinbase, unknown]);
+        let change = OutPoint::new(first.txid(), 0);
+        let second = spend(&[change]);
+        block.txdata.push(first.clone());
+        block.txdata.push(second);
+
+        assert_eq!(
+            block.all_spent_outpoints(),
+            vec![(coinbase, 1, 0), (unknown, 1, 1), (change, 2, 0)]
+        );
+        assert_eq!(block.transactions_spending_outpoint(&coinbase), vec![(1, 0)]);
+        assert_eq!(block.transactions_spending_outpoint(&change), vec![(2, 0)]);
+        assert!(block.transactions_spending_outpoint(&OutPoint::null()).is_empty());
+        assert!(block.transactions_spending_outpoint(&OutPoint::new(first.txid(), 1)).is_empty());
+
+        // A double spend within the block.
+        block.txdata.push(spend(&[unknown]));
+        assert_eq!(block.transactions_spending_outpoint(&unknown), vec![(1, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn header_hash_legacy() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let header = genesis_block(Network::Bitcoin).header;
+        let bytes = serialize(&header);
+        assert_eq!(bytes.len(), 80);
+        assert_eq!(header.hash_legacy(), BlockHash::hash(&bytes));
+        assert_eq!(header.hash_legacy(), header.block_hash());
+    }
+
+    #[test]
+    fn compute_next_target() {
+        use crate::blockdata::constants::{genesis_block, DIFFCHANGE_TIMESPAN};
+        use crate::network::Network;
+
+        let first = genesis_block(Network::Bitcoin).header;
+
+        // A window mined instantly is clamped to a four-fold difficulty increase.
+        let next = Header::compute_next_target(&first, &first);
+        assert_eq!(next, CompactTarget::from_consensus(0x1e03fffc));
+        assert_eq!(Target::from(next), first.target().min_difficulty_transition_threshold());
+        assert!(Target::from(next).difficulty() > first.difficulty());
+
+        let mut last = first.clone();
+        last.time += DIFFCHANGE_TIMESPAN;
+        assert_eq!(Header::compute_next_target(&first, &last), first.bits);
+
+        // Slower than expected at minimum difficulty stays at minimum difficulty.
+        last.time += 2 * DIFFCHANGE_TIMESPAN;
+        let next = Header::compute_next_target(&first, &last);
+        assert!(next.is_at_minimum_difficulty(Network::Bitcoin));
+
+        let mut hard = first.clone();
+        hard.bits = CompactTarget::from_consensus(0x1c0ffff0);
+        last.bits = hard.bits;
+        last.time = hard.time + 2 * DIFFCHANGE_TIMESPAN;
+        assert_eq!(
+            Header::compute_next_target(&hard, &last),
+            CompactTarget::from_consensus(0x1c1fffe0)
+        );
+    }
+
+    #[test]
+    fn total_chain_work() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Bitcoin).header;
+        let headers = vec![genesis.clone(); 10];
+        let work = Work::from_compact_target(genesis.bits);
+        let mut expected = Work::ZERO;
+        for _ in 0..10 {
+            expected += work;
+        }
+        assert_eq!(Header::total_chain_work(&headers), expected);
+        assert_eq!(Header::total_chain_work(&headers[..1]), genesis.work());
+        assert_eq!(Header::total_chain_work(&[]), Work::ZERO);
+        assert_eq!(
+            Header::total_chain_work(&headers).cmp_chain(Header::total_chain_work(&headers[..9])),
+            core::cmp::Ordering::Greater
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test-helpers")]
+    fn mine_regtest() {
+        use crate::blockdata::constants::{genesis_block, REGTEST_MIN_DIFFICULTY};
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Regtest).header;
+        let mut header = Header {
+            prev_blockhash: genesis.block_hash(),
+            time: genesis.time + 60,
+            nonce: 0,
+            ..genesis
+        };
+        let nonce = header.mine_regtest(REGTEST_MIN_DIFFICULTY);
+        assert_eq!(header.nonce, nonce);
+        assert_eq!(header.bits, REGTEST_MIN_DIFFICULTY);
+        let target = Target::from_compact(REGTEST_MIN_DIFFICULTY);
+        assert!(target.is_met_by(header.block_hash()));
+        assert_eq!(header.validate_pow(target), Ok(header.block_hash()));
+    }
+
+    #[test]
+    fn batch_validate() {
+        use crate::blockdata::constants::REGTEST_MIN_DIFFICULTY;
+
+        fn mine(prev: &Header) -> Header {
+            let mut header = prev.clone();
+            header.prev_blockhash = prev.block_hash();
+            header.time = prev.time + 60;
+            while header.validate_pow(header.target()).is_err() {
+                header.nonce += 1;
+            }
+            header
+        }
+
+        let mut first = Header {
+            version: Version::ONE,
+            prev_blockhash: BlockHash::all_zeros(),
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: 1_700_000_000,
+            bits: REGTEST_MIN_DIFFICULTY,
+            nonce: 0,
+            aux_data: None,
+        };
+        while first.validate_pow(first.target()).is_err() {
+            first.nonce += 1;
+        }
+        let mut headers = vec![first];
+        for _ in 0..12 {
+            let next = mine(headers.last().unwrap());
+            headers.push(next);
+        }
+
+        let pow = |h: &Header| h.validate_pow(h.target()).is_ok();
+
+        let hashes = Header::batch_validate(&headers, 1, REGTEST_MIN_DIFFICULTY, pow).unwrap();
+        assert_eq!(hashes, headers.iter().map(Header::block_hash).collect::<Vec<_>>());
+        assert_eq!(
+            Header::batch_validate(&headers, 1, CompactTarget::from_consensus(0x1e0ffff0), pow),
+            Err(HeaderValidationError::BadTarget {
+                height: 1,
+                expected: CompactTarget::from_consensus(0x1e0ffff0),
+                got: REGTEST_MIN_DIFFICULTY,
+            })
+        );
+
+        let mut broken = headers.clone();
+        broken[3].prev_blockhash = BlockHash::all_zeros();
+        assert_eq!(
+            Header::batch_validate(&broken, 1, REGTEST_MIN_DIFFICULTY, pow),
+            Err(HeaderValidationError::BadPrevBlockhash { height: 4 })
+        );
+
+        // Older than the median of the previous 11 headers, re-mine it to isolate the error.
+        let mut broken = headers.clone();
+        broken[12].time = broken[6].time;
+        broken[12].nonce = 0;
+        while broken[12].validate_pow(broken[12].target()).is_err() {
+            broken[12].nonce += 1;
+        }
+        assert_eq!(
+            Header::batch_validate(&broken, 1, REGTEST_MIN_DIFFICULTY, pow),
+            Err(HeaderValidationError::TimeTooOld { height: 13 })
+        );
+
+        // The proof of work check is up to the caller.
+        let reject_fifth = |h: &Header| h.block_hash() != headers[4].block_hash();
+        assert_eq!(
+            Header::batch_validate(&headers, 1, REGTEST_MIN_DIFFICULTY, reject_fifth),
+            Err(HeaderValidationError::BadProofOfWork { height: 5 })
+        );
+        assert!(Header::batch_validate(&headers, 1, REGTEST_MIN_DIFFICULTY, |_| true).is_ok());
+
+        assert_eq!(
+            Header::batch_validate(&headers, u32::MAX, REGTEST_MIN_DIFFICULTY, pow),
+            Err(HeaderValidationError::HeightOverflow { index: 1 })
+        );
+    }
+
+    #[test]
+    fn get_transaction() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Bitcoin);
+        let txid = genesis.txdata[0].txid();
+        assert_eq!(genesis.get_transaction(&txid), Some(&genesis.txdata[0]));
+        assert_eq!(genesis.get_transaction(&Txid::all_zeros()), None);
+
+        let mut txs = genesis.transactions_with_index();
+        assert_eq!(txs.next(), Some((txid, &genesis.txdata[0])));
+        assert_eq!(txs.next(), None);
+
+        #[cfg(feature = "std")]
+        {
+            let index = genesis.build_txid_index();
+            assert_eq!(index.len(), 1);
+            assert_eq!(index.get(&txid), Some(&0));
+        }
+    }
+
+    #[test]
+    fn chain_work_ordering() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let easy = genesis_block(Network::Bitcoin);
+        let mut hard = easy.clone();
+        hard.header.bits = CompactTarget::from_consensus(0x1b0404cb);
+
+        assert_eq!(easy.chain_work(), easy.header.work());
+        assert!(easy.chain_work() < hard.chain_work());
+        assert!(easy.chain_work() + easy.chain_work() < hard.chain_work());
+    }
+
+    #[test]
+    fn validate_coinbase_bip34() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::blockdata::opcodes::all::OP_NOP;
+        use crate::network::Network;
+
+        fn block_at(script_sig: script::ScriptBuf) -> Block {
+            let mut block = genesis_block(Network::Bitcoin);
+            block.txdata[0].input[0].script_sig = script_sig;
+            block
+        }
+
+        for &height in &[0u32, 1, 100_000] {
+            let script_sig = script::Builder::new()
+                .push_int(height.into())
+                .push_slice(b"extra nonce")
+                .into_script();
+            let block = block_at(script_sig);
+            assert_eq!(block.validate_coinbase_bip34(height), Ok(()));
+            assert_eq!(
+                block.validate_coinbase_bip34(height + 1),
+                Err(CoinbaseError::WrongHeight { actual: height.into(), expected: height + 1 })
+            );
+        }
+
+        // The genesis coinbase predates BIP34 and starts with the difficulty bits.
+        let genesis = genesis_block(Network::Bitcoin);
+        assert_eq!(
+            genesis.validate_coinbase_bip34(0),
+            Err(CoinbaseError::WrongHeight { actual: 486604799, expected: 0 })
+        );
+
+        let block = block_at(script::Builder::new().push_opcode(OP_NOP).into_script());
+        assert_eq!(block.validate_coinbase_bip34(1), Err(CoinbaseError::MissingHeight));
+
+        let mut block = genesis_block(Network::Bitcoin);
+        block.txdata.clear();
+        assert_eq!(block.validate_coinbase_bip34(1), Err(CoinbaseError::NotCoinbase));
+    }
+
+    #[test]
+    fn auxpow_header_roundtrip() {
+        use crate::blockdata::constants::genesis_block;
+        use crate::network::Network;
+
+        let genesis = genesis_block(Network::Bitcoin);
+        let plain = genesis.header.clone();
+        assert!(!plain.is_auxpow());
+        assert_eq!(serialize(&plain).len(), 80);
+        assert_eq!(deserialize::<Header>(&serialize(&plain)).unwrap(), plain);
+
+        let aux_data = AuxPow {
+            coinbase_tx: genesis.txdata[0].clone(),
+            block_hash: BlockHash::from_byte_array([0x11; 32]),
+            coinbase_branch: MerkleBranch {
+                hashes: vec![BlockHash::from_byte_array([0x22; 32])],
+                side_mask: 0,
+            },
+            blockchain_branch: MerkleBranch {
+                hashes: vec![
+                    BlockHash::from_byte_array([0x33; 32]),
+                    BlockHash::from_byte_array([0x44; 32]),
+                ],
+                side_mask: 2,
+            },
+            parent_block: SimpleHeader { nonce: 42, ..plain.to_simple_header() },
+        };
+        let header = Header {
+            version: Version::from_consensus(0x0062_0104),
+            prev_blockhash: plain.block_hash(),
+            merkle_root: plain.merkle_root,
+            time: plain.time + 60,
+            bits: plain.bits,
+            nonce: 0,
+            aux_data: Some(aux_data.clone()),
+        };
+        assert!(header.is_auxpow());
+
+        let encoded = serialize(&header);
+        assert_eq!(encoded.len(), 80 + serialize(&aux_data).len());
+        let decoded: Header = deserialize(&encoded).unwrap();
+        assert_eq!(decoded.version, header.version);
+        assert_eq!(decoded.prev_blockhash, header.prev_blockhash);
+        assert_eq!(decoded.merkle_root, header.merkle_root);
+        assert_eq!(decoded.time, header.time);
+        assert_eq!(decoded.bits, header.bits);
+        assert_eq!(decoded.nonce, header.nonce);
+        assert_eq!(decoded.aux_data, Some(aux_data));
+        assert_eq!(decoded, header);
+
+        // Only the base header is hashed, but it cannot be encoded without its aux_data.
+        let missing = Header { aux_data: None, ..header };
+        assert_eq!(missing.block_hash(), decoded.block_hash());
+        assert!(missing.consensus_encode(&mut Vec::new()).is_err());
+    }
+
     #[test]
     fn auxpow_with_segwit_header() {
         let swahdr = hex!("0401620052e3397a263aa994b1cbade1df094843ee3d4414ec50f700df3e9fe13cde30cca0ceebf962d8757ec7cd8315adf9c5b75a9bf28a7dcda56de9393637f983cc1f0fc64e6583a8011a00000000020000000001010000000000000000000000000000000000000000000000000000000000000000ffffffff4403b05527fabe6d6d1c1e91303111f235329180ea89fb2976dd40568db66c3bae570568b007b87a2501000000000000005a554c55506f6f4c2d4c5443000005432cc40200ffffffff02f100a125000000001976a914f8394bea504520ac3ef09fd6a5adf70bede47dae88ac0000000000000000266a24aa21a9edde594137969fb1ab44095d93e452b01b20b9bcc477e8c913eba0e0645f39bf6a012000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000b486809f957d7a60782215849b4228e87886b3f7fffbb8c10d0348a7aba810669aa2d585bb12a33e650844b5425c7a483278d63c3198f106b1db5205068c839d60eaefd3e1bf4144601dfbcbaf3dcd60d99d12dc3298b3df36150c745dd9763124002f88ee76619055019f7b1342638a6ad14eafb5204e59d5477a2e48f2fa5228699ae1a30c3c20c5fb669720e854b72190184652c31ead665049ddfd2f2ecef7c239308b9c51ee953c7fc616d74f3dedacfaeed7ea814a2f12555d1c2c9cf745f671f6a17b45f7f81fd005a461887540a1ca32b0e9cc8e2a700a2dfe08ad7d20dacf6e7f57719b5ee5f4911482a8ad08d649406819c565af927714e827f61ab3775c9b3080c18b38b6baff0b9a366da18682d275d16010538bb131c8ad53de8091f013fd342a7abab2d81e3a9034d848bb861dbf3ce3d6b706b63ebf4098242e110807f36604de297359be4ebf8ac927249ad2a1a9167b732cce2fb83775af100000000000000000000000020964243892e5af578b1afd1bce69ba7390aeb9c2858665135b03793d7f77950cd44f14269978a2c988e0c509d5dc6932b7d6685b9b423beddd633db567c5678e218c64e650592001a629d0b09");
@@ -683,6 +1493,22 @@ mod tests {
         println!("{:?}", hdr);
     }
 
+    #[test]
+    fn validate_chain_id() {
+        let version = Version::from_consensus(0x0062_0104);
+        assert!(version.is_auxpow());
+        assert_eq!(version.chain_id(), 0x0062);
+        assert_eq!(version.validate_chain_id(0x0062), Ok(()));
+        assert_eq!(
+            version.validate_chain_id(0x0001),
+            Err(ChainIdError::Mismatch { actual: 0x0062, expected: 0x0001 })
+        );
+
+        assert_eq!(Version::TWO.validate_chain_id(0x0062), Err(ChainIdError::OutOfRange(0)));
+        let negative = Version::from_consensus(0x8062_0104_u32 as i32);
+        assert_eq!(negative.validate_chain_id(0x0062), Err(ChainIdError::OutOfRange(0x8062)));
+    }
+
     #[test]
     fn block2() {
         let dd = hex!("010000009156352c1818b32e90c9e792efd6a11a82fe7956a630f03bbee236cedae3911a1c525f1049e519256961f407e96e22aef391581de98686524ef500769f777e5fafeda352f0ff0f1e001083540101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0e04afeda3520102062f503253482fffffffff01004023ef3806000023210338bf57d51a50184cf5ef0dc42ecd519fb19e24574c057620262cc1df94da2ae5ac00000000");
@@ -17,8 +17,10 @@ use crate::blockdata::block::{self, Block};
 use crate::blockdata::locktime::absolute;
 use crate::blockdata::opcodes::all::*;
 use crate::blockdata::script;
+use crate::blockdata::script::witness_version::WitnessVersion;
 use crate::blockdata::transaction::{self, OutPoint, Sequence, Transaction, TxIn, TxOut};
 use crate::blockdata::witness::Witness;
+use crate::hash_types::BlockHash;
 use crate::internal_macros::impl_bytes_newtype;
 use crate::network::Network;
 use crate::pow::CompactTarget;
@@ -43,6 +45,9 @@ pub const MIN_TRANSACTION_WEIGHT: u32 = 4 * 60;
 pub const WITNESS_SCALE_FACTOR: usize = 4;
 /// The maximum allowed number of signature check operations in a block.
 pub const MAX_BLOCK_SIGOPS_COST: i64 = 80_000;
+/// The factor that legacy and P2SH sigops are multiplied by in the sigop cost, witness sigops
+/// are counted once (BIP141).
+pub const LEGACY_SIGOP_SCALE: usize = 4;
 /// Mainnet (Lebowkis) pubkey address prefix.
 pub const PUBKEY_ADDRESS_PREFIX_MAIN: u8 = 12; // Lebowkis mainnet P2PKH
 /// Mainnet (Lebowkis) script address prefix.
@@ -63,6 +68,35 @@ pub const SUBSIDY_HALVING_INTERVAL: u32 = 210_000;
 pub const MAX_SCRIPTNUM_VALUE: u32 = 0x80000000; // 2^31
 /// Number of blocks needed for an output from a coinbase transaction to be spendable.
 pub const COINBASE_MATURITY: u32 = 100;
+/// The witness version used for new SegWit addresses.
+pub const DEFAULT_SEGWIT_VERSION: WitnessVersion = WitnessVersion::V0;
+/// The maximum amount of money that can exist, see [`Amount::from_sat_checked`].
+pub const MAX_MONEY: Amount = Amount::MAX_MONEY;
+/// The minimum difficulty on mainnet, which is also the difficulty of the genesis block.
+pub const MAINNET_MIN_DIFFICULTY: CompactTarget = CompactTarget::from_consensus(GENESIS_BITS);
+/// The minimum difficulty on regtest, corresponding to [`Target::MAX_ATTAINABLE_REGTEST`].
+///
+/// [`Target::MAX_ATTAINABLE_REGTEST`]: crate::pow::Target::MAX_ATTAINABLE_REGTEST
+pub const REGTEST_MIN_DIFFICULTY: CompactTarget = CompactTarget::from_consensus(0x207fffff);
+
+/// Timestamp of the Lebowkis genesis block.
+const GENESIS_TIME: u32 = 1374378315;
+/// Nonce of the Lebowkis genesis block.
+const GENESIS_NONCE: u32 = 1369296945;
+/// Compact target of the Lebowkis genesis block.
+const GENESIS_BITS: u32 = 0x1e0ffff0;
+/// Value of the Lebowkis genesis coinbase output in satoshis (19.98 LBW).
+const GENESIS_COINBASE_VALUE: u64 = 1_998_000_000;
+/// Message pushed by the scriptSig of the Lebowkis genesis coinbase.
+const GENESIS_MESSAGE: &[u8; 68] =
+    b"Six Flags coaster victim concerned about seat. USAToday - 07.20.2013";
+/// Public key paid to by the Lebowkis genesis coinbase output.
+const GENESIS_OUTPUT_PUBKEY: [u8; 65] = hex!("040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc8bc4a8e4d3eb4b10f4d4604fa08dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9");
+/// Merkle root of the Lebowkis genesis block, in internal byte order.
+const GENESIS_MERKLE_ROOT: [u8; 32] = [
+    0x0f, 0x59, 0xd1, 0xf5, 0x1e, 0xac, 0xae, 0x12, 0x7a, 0x32, 0xe9, 0x18, 0xcf, 0x6c, 0x46, 0x0a,
+    0x89, 0xd4, 0xf6, 0xe4, 0x24, 0x79, 0x3f, 0x29, 0xbb, 0x5c, 0xfe, 0x42, 0x7d, 0x2b, 0x67, 0xa3,
+];
 
 /// Constructs and returns the coinbase (and only) transaction of the Lebowkis genesis block.
 fn bitcoin_genesis_tx() -> Transaction {
@@ -78,7 +112,7 @@ fn bitcoin_genesis_tx() -> Transaction {
     let in_script = script::Builder::new()
         .push_int(486604799)
         .push_int_non_minimal(4)
-        .push_slice(b"Six Flags coaster victim concerned about seat. USAToday - 07.20.2013")
+        .push_slice(GENESIS_MESSAGE)
         .into_script();
     ret.input.push(TxIn {
         previous_output: OutPoint::null(),
@@ -88,10 +122,12 @@ fn bitcoin_genesis_tx() -> Transaction {
     });
 
     // Outputs
-    let script_bytes = hex!("040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc8bc4a8e4d3eb4b10f4d4604fa08dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9");
-    let out_script =
-        script::Builder::new().push_slice(script_bytes).push_opcode(OP_CHECKSIG).into_script();
-    ret.output.push(TxOut { value: Amount::from_sat(1998000000), script_pubkey: out_script }); // 19.98 LBW
+    let out_script = script::Builder::new()
+        .push_slice(GENESIS_OUTPUT_PUBKEY)
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+    ret.output
+        .push(TxOut { value: Amount::from_sat(GENESIS_COINBASE_VALUE), script_pubkey: out_script });
 
     // end
     ret
@@ -108,9 +144,9 @@ pub fn genesis_block(network: Network) -> Block {
                 version: block::Version::ONE,
                 prev_blockhash: Hash::all_zeros(),
                 merkle_root,
-                time: 1374378315,
-                bits: CompactTarget::from_consensus(0x1e0ffff0),
-                nonce: 1369296945,
+                time: GENESIS_TIME,
+                bits: CompactTarget::from_consensus(GENESIS_BITS),
+                nonce: GENESIS_NONCE,
                 aux_data: None,
             },
             txdata,
@@ -120,9 +156,9 @@ pub fn genesis_block(network: Network) -> Block {
                 version: block::Version::ONE,
                 prev_blockhash: Hash::all_zeros(),
                 merkle_root,
-                time: 1374378315,
-                bits: CompactTarget::from_consensus(0x1e0ffff0),
-                nonce: 1369296945,
+                time: GENESIS_TIME,
+                bits: CompactTarget::from_consensus(GENESIS_BITS),
+                nonce: GENESIS_NONCE,
                 aux_data: None,
             },
             txdata,
@@ -132,9 +168,9 @@ pub fn genesis_block(network: Network) -> Block {
                 version: block::Version::ONE,
                 prev_blockhash: Hash::all_zeros(),
                 merkle_root,
-                time: 1374378315,
-                bits: CompactTarget::from_consensus(0x1e0ffff0),
-                nonce: 1369296945,
+                time: GENESIS_TIME,
+                bits: CompactTarget::from_consensus(GENESIS_BITS),
+                nonce: GENESIS_NONCE,
                 aux_data: None,
             },
             txdata,
@@ -144,9 +180,9 @@ pub fn genesis_block(network: Network) -> Block {
                 version: block::Version::ONE,
                 prev_blockhash: Hash::all_zeros(),
                 merkle_root,
-                time: 1374378315,
-                bits: CompactTarget::from_consensus(0x1e0ffff0),
-                nonce: 1369296945,
+                time: GENESIS_TIME,
+                bits: CompactTarget::from_consensus(GENESIS_BITS),
+                nonce: GENESIS_NONCE,
                 aux_data: None,
             },
             txdata,
@@ -154,6 +190,84 @@ pub fn genesis_block(network: Network) -> Block {
     }
 }
 
+/// Hash of the Lebowkis genesis block, in internal byte order.
+const GENESIS_BLOCK_HASH: [u8; 32] = [
+    0x30, 0x71, 0x6b, 0x00, 0xee, 0xc8, 0xde, 0x38, 0xcb, 0xcb, 0xb6, 0x22, 0x54, 0x99, 0x88, 0xe6,
+    0xd7, 0xca, 0x8a, 0x88, 0x82, 0x93, 0xca, 0x25, 0x4d, 0x85, 0xcd, 0x3f, 0x41, 0x0d, 0x4b, 0xd1,
+];
+
+/// Returns the hash of the genesis block of `network`.
+///
+/// This is the same as `genesis_block(network).block_hash()` without constructing or hashing the
+/// block. All Lebowkis networks currently share the same genesis block.
+pub const fn genesis_block_hash(network: Network) -> BlockHash {
+    match network {
+        Network::Bitcoin | Network::Testnet | Network::Signet | Network::Regtest =>
+            BlockHash::from_byte_array_const(GENESIS_BLOCK_HASH),
+    }
+}
+
+/// The consensus encoding of the Lebowkis genesis block.
+const GENESIS_BLOCK_BYTES: [u8; 284] = hex!(
+    "0100000000000000000000000000000000000000000000000000000000000000000000000f59d1f51eacae127a32e918\
+    cf6c460a89d4f6e424793f29bb5cfe427d2b67a34b59eb51f0ff0f1e31d09d5101010000000100000000000000000000\
+    00000000000000000000000000000000000000000000ffffffff4c04ffff001d01044453697820466c61677320636f61\
+    737465722076696374696d20636f6e6365726e65642061626f757420736561742e20555341546f646179202d2030372e\
+    32302e32303133ffffffff01800f1777000000004341040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc\
+    8bc4a8e4d3eb4b10f4d4604fa08dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9ac00000000"
+);
+
+/// Returns `true` if `bytes` contains `expected` at `offset`, usable in const context.
+const fn bytes_eq_at(bytes: &[u8], offset: usize, expected: &[u8]) -> bool {
+    let mut i = 0;
+    while i < expected.len() {
+        if bytes[offset + i] != expected[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Checks that the encoded genesis block carries the genesis parameters, evaluated at compile
+/// time.
+///
+/// This covers the header fields, the coinbase value and the coinbase scripts. SHA256d can not be
+/// computed in a const fn, so the merkle root and block hash are only compared to constants here
+/// and checked against the hashes of the block by the tests.
+const fn validate_genesis_bytes() -> bool {
+    let b = &GENESIS_BLOCK_BYTES;
+    // Header: version, previous block hash, merkle root, time, bits and nonce.
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]]) == 1
+        && bytes_eq_at(b, 4, &[0; 32])
+        && bytes_eq_at(b, 36, &GENESIS_MERKLE_ROOT)
+        && u32::from_le_bytes([b[68], b[69], b[70], b[71]]) == GENESIS_TIME
+        && u32::from_le_bytes([b[72], b[73], b[74], b[75]]) == GENESIS_BITS
+        && u32::from_le_bytes([b[76], b[77], b[78], b[79]]) == GENESIS_NONCE
+        // The scriptSig length, its `<486604799> <4>` pushes and the push of the message.
+        && bytes_eq_at(b, 122, &[0x4c, 0x04, 0xff, 0xff, 0x00, 0x1d, 0x01, 0x04, 0x44])
+        && bytes_eq_at(b, 131, GENESIS_MESSAGE)
+        // The output value and its scriptPubKey, `<pubkey> OP_CHECKSIG`.
+        && u64::from_le_bytes([b[204], b[205], b[206], b[207], b[208], b[209], b[210], b[211]])
+            == GENESIS_COINBASE_VALUE
+        && bytes_eq_at(b, 212, &[0x43, 0x41])
+        && bytes_eq_at(b, 214, &GENESIS_OUTPUT_PUBKEY)
+        && b[279] == 0xac
+}
+
+/// Returns the consensus encoding of the genesis block of `network`.
+///
+/// This is the same as `serialize(&genesis_block(network))` without constructing the block, for
+/// callers that need the raw block e.g. to bootstrap a block store.
+pub fn genesis_block_raw_bytes(network: Network) -> &'static [u8] {
+    internals::const_assert!(validate_genesis_bytes());
+
+    match network {
+        Network::Bitcoin | Network::Testnet | Network::Signet | Network::Regtest =>
+            &GENESIS_BLOCK_BYTES,
+    }
+}
+
 /// The uniquely identifying hash of the target blockchain.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ChainHash([u8; 32]);
@@ -227,6 +341,73 @@ mod test {
         );
     }
 
+    #[test]
+    fn genesis_hash() {
+        for &network in &[Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest] {
+            assert_eq!(genesis_block_hash(network), genesis_block(network).block_hash());
+        }
+        assert_eq!(
+            genesis_block_hash(Network::Bitcoin).to_string(),
+            "d14b0d413fcd854d25ca9382888acad7e688995422b6cbcb38dec8ee006b7130"
+        );
+    }
+
+    #[test]
+    fn genesis_raw_bytes() {
+        use crate::consensus::encode::deserialize;
+
+        for &network in &[Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest] {
+            let bytes = genesis_block_raw_bytes(network);
+            let block: Block = deserialize(bytes).unwrap();
+            assert_eq!(block, genesis_block(network));
+            assert_eq!(block.block_hash(), genesis_block_hash(network));
+            assert_eq!(serialize(&genesis_block(network)), bytes);
+
+            assert_eq!(block.header.version, block::Version::ONE);
+            assert_eq!(block.header.prev_blockhash, BlockHash::all_zeros());
+            assert_eq!(block.compute_merkle_root(), Some(block.header.merkle_root));
+            assert_eq!(block.header.merkle_root.to_byte_array(), GENESIS_MERKLE_ROOT);
+            assert_eq!(block.header.time, 1374378315);
+            assert_eq!(block.header.bits, CompactTarget::from_consensus(0x1e0ffff0));
+            assert_eq!(block.header.nonce, 1369296945);
+            assert_eq!(block.header.aux_data, None);
+            assert_eq!(block.txdata, vec![bitcoin_genesis_tx()]);
+        }
+    }
+
+    #[test]
+    fn crate_root_reexports() {
+        assert_eq!(crate::TARGET_BLOCK_SPACING, TARGET_BLOCK_SPACING);
+        assert_eq!(crate::COINBASE_MATURITY, COINBASE_MATURITY);
+        assert_eq!(crate::SUBSIDY_HALVING_INTERVAL, SUBSIDY_HALVING_INTERVAL);
+        assert_eq!(crate::DIFFCHANGE_INTERVAL, DIFFCHANGE_INTERVAL);
+        assert_eq!(crate::DIFFCHANGE_TIMESPAN, DIFFCHANGE_TIMESPAN);
+        assert_eq!(crate::MAX_MONEY, MAX_MONEY);
+        assert_eq!(crate::MAX_SCRIPT_ELEMENT_SIZE, MAX_SCRIPT_ELEMENT_SIZE);
+        assert_eq!(crate::WITNESS_SCALE_FACTOR, WITNESS_SCALE_FACTOR);
+        assert_eq!(crate::PUBKEY_ADDRESS_PREFIX_MAIN, 12);
+        assert_eq!(crate::SCRIPT_ADDRESS_PREFIX_MAIN, 8);
+        assert_eq!(crate::PUBKEY_ADDRESS_PREFIX_TEST, 13);
+        assert_eq!(crate::SCRIPT_ADDRESS_PREFIX_TEST, 9);
+        assert_eq!(crate::PUBKEY_ADDRESS_PREFIX_REGTEST, 47);
+        assert_eq!(crate::SCRIPT_ADDRESS_PREFIX_REGTEST, 5);
+        assert_eq!(crate::ChainHash::BITCOIN, ChainHash::BITCOIN);
+        assert_eq!(crate::Magic::from(Network::Bitcoin), Network::Bitcoin.magic());
+    }
+
+    #[test]
+    fn genesis_min_difficulty() {
+        let gen = genesis_block(Network::Bitcoin);
+        assert_eq!(gen.header.bits, MAINNET_MIN_DIFFICULTY);
+        assert!(gen.header.bits.is_at_minimum_difficulty(Network::Bitcoin));
+        assert!(!gen.header.bits.is_at_minimum_difficulty(Network::Regtest));
+        assert!(REGTEST_MIN_DIFFICULTY.is_at_minimum_difficulty(Network::Regtest));
+        assert_eq!(
+            crate::pow::Target::from_compact(REGTEST_MIN_DIFFICULTY),
+            crate::pow::Target::MAX_ATTAINABLE_REGTEST
+        );
+    }
+
     #[test]
     fn bitcoin_genesis_full_block() {
         let gen = genesis_block(Network::Bitcoin);
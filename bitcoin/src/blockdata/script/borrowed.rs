@@ -10,12 +10,13 @@ use hashes::Hash;
 use secp256k1::{Secp256k1, Verification};
 
 use super::PushBytes;
+use crate::address::Address;
 use crate::blockdata::opcodes::all::*;
 use crate::blockdata::opcodes::{self, Opcode};
 use crate::blockdata::script::witness_version::WitnessVersion;
 use crate::blockdata::script::{
     bytes_to_asm_fmt, Builder, Instruction, InstructionIndices, Instructions, ScriptBuf,
-    ScriptHash, WScriptHash,
+    ScriptHash, SigPushOnlyError, WScriptHash,
 };
 use crate::consensus::Encodable;
 use crate::key::{PublicKey, UntweakedPublicKey};
@@ -83,6 +84,13 @@ impl Script {
     #[inline]
     pub fn new() -> &'static Script { Script::from_bytes(&[]) }
 
+    /// Returns the scriptPubkey paying to `address`, same as [`Address::script_pubkey`].
+    ///
+    /// The returned script only uses minimal pushes (see [`Script::instructions_minimal`]) and is
+    /// never provably unspendable, for every address type.
+    #[inline]
+    pub fn for_address(address: &Address) -> ScriptBuf { address.script_pubkey() }
+
     /// Treat byte slice as `Script`
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> &Script {
@@ -130,6 +138,19 @@ impl Script {
         TapLeafHash::from_script(self, LeafVersion::TapScript)
     }
 
+    /// Returns the dedicated opcode pushing `n` onto the stack, if there is one.
+    ///
+    /// Only -1 (`OP_PUSHNUM_NEG1`), 0 (`OP_0`) and 1 to 16 (`OP_PUSHNUM_1` to `OP_PUSHNUM_16`)
+    /// have such an opcode, all other numbers must be pushed as data.
+    pub fn opcode_for_number(n: i64) -> Option<Opcode> {
+        match n {
+            -1 => Some(OP_PUSHNUM_NEG1),
+            0 => Some(opcodes::OP_0),
+            1..=16 => Some(Opcode::from(OP_PUSHNUM_1.to_u8() + (n - 1) as u8)),
+            _ => None,
+        }
+    }
+
     /// Returns the length in bytes of the script.
     #[inline]
     pub fn len(&self) -> usize { self.0.len() }
@@ -227,6 +248,26 @@ impl Script {
         true
     }
 
+    /// Checks that a scriptSig is push only for the `SCRIPT_VERIFY_SIGPUSHONLY` relay policy.
+    ///
+    /// Reports the first offending opcode and its byte offset in the script. The OP_PUSHNUM
+    /// operations are considered push operations but, unlike in [`Script::is_push_only`] and
+    /// Bitcoin Core's `IsPushOnly`, `OP_RESERVED` is not: it pushes nothing and fails the script
+    /// when executed, so a scriptSig containing it can never be valid.
+    pub fn check_sigpushonly(&self) -> Result<(), SigPushOnlyError> {
+        for inst in self.instruction_indices() {
+            match inst {
+                Err(e) => return Err(SigPushOnlyError::Script(e)),
+                Ok((_, Instruction::PushBytes(_))) => {}
+                Ok((_, Instruction::Op(op)))
+                    if op.to_u8() <= OP_PUSHNUM_16.to_u8() && op != OP_RESERVED => {}
+                Ok((index, Instruction::Op(opcode))) =>
+                    return Err(SigPushOnlyError::NonPushOpcode { index, opcode }),
+            }
+        }
+        Ok(())
+    }
+
     /// Checks whether a script pubkey is a P2PK output.
     ///
     /// You can obtain the public key, if its valid,
@@ -240,6 +281,7 @@ impl Script {
     /// This happens when the public key is invalid (e.g. the point not being on the curve).
     /// It also implies the script is unspendable.
     #[inline]
+    #[doc(alias = "p2pk_pubkey")]
     pub fn p2pk_public_key(&self) -> Option<PublicKey> {
         PublicKey::from_slice(self.p2pk_pubkey_bytes()?).ok()
     }
@@ -355,6 +397,13 @@ impl Script {
             && self.0[1] == OP_PUSHBYTES_20.to_u8()
     }
 
+    /// Checks whether a script is a segwit v0 program, i.e. a P2WPKH or P2WSH output.
+    ///
+    /// Applied to the redeem script of a P2SH input this detects nested (wrapped) segwit without
+    /// executing the script.
+    #[inline]
+    pub fn is_witness_script(&self) -> bool { self.is_p2wpkh() || self.is_p2wsh() }
+
     pub(crate) fn p2wpkh(&self) -> Option<&[u8; 20]> {
         if self.is_p2wpkh() {
             Some(self.0[2..].try_into().expect("is_v0_p2wpkh checks the length"))
@@ -363,6 +412,26 @@ impl Script {
         }
     }
 
+    /// Returns the 20-byte witness program if this is a P2WPKH script pubkey (`OP_0 <20 bytes>`).
+    #[inline]
+    pub fn v0_p2wpkh_program(&self) -> Option<&[u8]> {
+        if self.is_p2wpkh() {
+            Some(&self.0[2..])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the 32-byte witness program if this is a P2WSH script pubkey (`OP_0 <32 bytes>`).
+    #[inline]
+    pub fn v0_p2wsh_program(&self) -> Option<&[u8]> {
+        if self.is_p2wsh() {
+            Some(&self.0[2..])
+        } else {
+            None
+        }
+    }
+
     /// Checks whether a script pubkey is a P2TR output.
     #[inline]
     #[deprecated(since = "0.31.0", note = "use is_p2tr instead")]
@@ -472,6 +541,23 @@ impl Script {
     /// so do not use this to try and estimate if a taproot script goes over the sigop budget.)
     pub fn count_sigops_legacy(&self) -> usize { self.count_sigops_internal(false) }
 
+    /// Counts the sigops of spending this witness program as defined in BIP141.
+    ///
+    /// P2WPKH counts as 1 sigop and P2WSH counts the sigops of `witness_script` (the last witness
+    /// item of the spending input) using accurate counting. Other scripts, including taproot,
+    /// count as 0. Witness sigops are not scaled by [`WITNESS_SCALE_FACTOR`] in the sigop cost.
+    ///
+    /// [`WITNESS_SCALE_FACTOR`]: crate::blockdata::constants::WITNESS_SCALE_FACTOR
+    pub fn count_sigops_witness(&self, witness_script: Option<&Script>) -> usize {
+        if self.is_p2wpkh() {
+            1
+        } else if self.is_p2wsh() {
+            witness_script.map(|s| s.count_sigops()).unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
     fn count_sigops_internal(&self, accurate: bool) -> usize {
         let mut n = 0;
         let mut pushnum_cache = None;
@@ -541,6 +627,7 @@ impl Script {
     ///
     /// To force minimal pushes, use [`Self::instruction_indices_minimal`].
     #[inline]
+    #[doc(alias = "instructions_with_offset")]
     pub fn instruction_indices(&self) -> InstructionIndices {
         InstructionIndices::from_instructions(self.instructions())
     }
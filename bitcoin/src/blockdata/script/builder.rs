@@ -1,25 +1,33 @@
 // SPDX-License-Identifier: CC0-1.0
 
+use core::convert::TryFrom;
 use core::default::Default;
 use core::fmt;
 
+use internals::write_err;
 use secp256k1::XOnlyPublicKey;
 
-use crate::blockdata::locktime::absolute;
+use crate::blockdata::locktime::{absolute, relative};
 use crate::blockdata::opcodes::all::*;
-use crate::blockdata::opcodes::{self, Opcode};
-use crate::blockdata::script::{opcode_to_verify, write_scriptint, PushBytes, Script, ScriptBuf};
+use crate::blockdata::opcodes::Opcode;
+use crate::blockdata::script::{
+    opcode_to_verify, write_scriptint, Instruction, OversizeError, PushBytes, Script, ScriptBuf,
+    ScriptHash,
+};
 use crate::blockdata::transaction::Sequence;
-use crate::key::PublicKey;
+use crate::key::{PubkeyHash, PublicKey};
 use crate::prelude::*;
 
 /// An Object which can be used to construct a script piece by piece.
+///
+/// Besides the script and its last opcode, the builder keeps the conditionals left open by the
+/// script, each with whether it already has an `OP_ELSE`, for [`Builder::push_opcode_checked`].
 #[derive(PartialEq, Eq, Clone)]
-pub struct Builder(ScriptBuf, Option<Opcode>);
+pub struct Builder(ScriptBuf, Option<Opcode>, Vec<bool>);
 
 impl Builder {
     /// Creates a new empty script.
-    pub fn new() -> Self { Builder(ScriptBuf::new(), None) }
+    pub fn new() -> Self { Builder(ScriptBuf::new(), None, Vec::new()) }
 
     /// Returns the length in bytes of the script.
     pub fn len(&self) -> usize { self.0.len() }
@@ -32,24 +40,24 @@ impl Builder {
     /// Integers are encoded as little-endian signed-magnitude numbers, but there are dedicated
     /// opcodes to push some small integers.
     pub fn push_int(self, data: i64) -> Builder {
-        // We can special-case -1, 1-16
-        if data == -1 || (1..=16).contains(&data) {
-            let opcode = Opcode::from((data - 1 + opcodes::OP_TRUE.to_u8() as i64) as u8);
-            self.push_opcode(opcode)
-        }
-        // We can also special-case zero
-        else if data == 0 {
-            self.push_opcode(opcodes::OP_0)
-        }
-        // Otherwise encode it as data
-        else {
-            self.push_int_non_minimal(data)
+        match Script::opcode_for_number(data) {
+            Some(opcode) => self.push_opcode(opcode),
+            // Otherwise encode it as data
+            None => self.push_int_non_minimal(data),
         }
     }
 
+    /// Adds instructions to push an integer onto the stack using the minimal encoding.
+    ///
+    /// This is the same as [`Builder::push_int`], small integers are pushed with their dedicated
+    /// opcodes (e.g. `OP_4`) as required by the `MINIMALDATA` policy.
+    pub fn push_canonical_int(self, data: i64) -> Builder { self.push_int(data) }
+
     /// Adds instructions to push an integer onto the stack without optimization.
     ///
-    /// This uses the explicit encoding regardless of the availability of dedicated opcodes.
+    /// This uses the explicit encoding regardless of the availability of dedicated opcodes. It is
+    /// only needed to reproduce existing scripts such as the genesis coinbase scriptSig, new
+    /// scripts should use [`Builder::push_int`].
     pub(in crate::blockdata) fn push_int_non_minimal(self, data: i64) -> Builder {
         let mut buf = [0u8; 8];
         let len = write_scriptint(&mut buf, data);
@@ -63,6 +71,50 @@ impl Builder {
         self
     }
 
+    /// Adds instructions to push arbitrary bytes onto the stack without checking them against
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`].
+    ///
+    /// This is an escape hatch for building scripts that are not meant to be executed (e.g. in
+    /// tests), prefer [`PushBytes::new`] and [`Builder::push_slice`] otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If `data` is longer than the 2^32-1 bytes a push can encode.
+    ///
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`]: crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+    pub fn push_slice_unchecked(self, data: &[u8]) -> Builder {
+        let data = <&PushBytes>::try_from(data).expect("data push exceeds the encoding limit");
+        self.push_slice(data)
+    }
+
+    /// Adds instructions to push `data` onto the stack.
+    ///
+    /// This is [`Builder::push_slice`] for a plain byte slice, so like
+    /// [`Builder::push_slice_unchecked`] the data is not checked against
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`].
+    ///
+    /// # Panics
+    ///
+    /// If `data` is longer than the 2^32-1 bytes a push can encode.
+    ///
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`]: crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+    pub fn push_data(self, data: &[u8]) -> Builder { self.push_slice_unchecked(data) }
+
+    /// Adds instructions to push the already checked `data` onto the stack.
+    pub fn push_bytes_ref(self, data: &PushBytes) -> Builder { self.push_slice(data) }
+
+    /// Adds instructions to push the UTF-8 encoding of `s` onto the stack.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError::Oversize`] if the encoded string is longer than
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`].
+    ///
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`]: crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+    pub fn push_str(self, s: &str) -> Result<Builder, BuilderError> {
+        Ok(self.push_slice(PushBytes::new(s.as_bytes())?))
+    }
+
     /// Adds instructions to push a public key onto the stack.
     pub fn push_key(self, key: &PublicKey) -> Builder {
         if key.compressed {
@@ -77,13 +129,50 @@ impl Builder {
         self.push_slice(x_only_key.serialize())
     }
 
+    /// Adds a complete P2PKH scriptPubkey paying to `pubkey_hash`.
+    ///
+    /// This is `OP_DUP OP_HASH160 <pubkey_hash> OP_EQUALVERIFY OP_CHECKSIG`.
+    pub fn push_p2pkh(self, pubkey_hash: &PubkeyHash) -> Builder {
+        self.push_opcode(OP_DUP)
+            .push_opcode(OP_HASH160)
+            .push_slice(pubkey_hash)
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_CHECKSIG)
+    }
+
+    /// Adds a complete P2SH scriptPubkey paying to `script_hash`.
+    ///
+    /// This is `OP_HASH160 <script_hash> OP_EQUAL`.
+    pub fn push_p2sh(self, script_hash: &ScriptHash) -> Builder {
+        self.push_opcode(OP_HASH160).push_slice(script_hash).push_opcode(OP_EQUAL)
+    }
+
     /// Adds a single opcode to the script.
     pub fn push_opcode(mut self, data: Opcode) -> Builder {
         self.0.push_opcode(data);
         self.1 = Some(data);
+        track_conditional(&mut self.2, data);
         self
     }
 
+    /// Adds a single opcode to the script, checking that it keeps the conditional structure of
+    /// the script sound.
+    ///
+    /// `OP_ELSE` and `OP_ENDIF` must close an open `OP_IF` or `OP_NOTIF`, and each conditional
+    /// may have at most one `OP_ELSE`. Other opcodes are pushed as with [`Builder::push_opcode`],
+    /// which remains available to build arbitrary scripts.
+    pub fn push_opcode_checked(self, opcode: Opcode) -> Result<Builder, BuilderError> {
+        let balanced = match opcode {
+            OP_ELSE => self.2.last() == Some(&false),
+            OP_ENDIF => !self.2.is_empty(),
+            _ => true,
+        };
+        if !balanced {
+            return Err(BuilderError::UnbalancedControlFlow { opcode });
+        }
+        Ok(self.push_opcode(opcode))
+    }
+
     /// Adds an `OP_VERIFY` to the script or replaces the last opcode with VERIFY form.
     ///
     /// Some opcodes such as `OP_CHECKSIG` have a verify variant that works as if `VERIFY` was
@@ -110,6 +199,15 @@ impl Builder {
         self.push_int(lock_time.to_consensus_u32().into())
     }
 
+    /// Adds instructions to push a relative lock time onto the stack, as checked by `OP_CSV`.
+    pub fn push_relative_lock_time(self, lock_time: relative::LockTime) -> Builder {
+        let sequence = match lock_time {
+            relative::LockTime::Blocks(height) => Sequence::from_height(height.value()),
+            relative::LockTime::Time(time) => Sequence::from_512_second_intervals(time.value()),
+        };
+        self.push_sequence(sequence)
+    }
+
     /// Adds instructions to push a sequence number onto the stack.
     pub fn push_sequence(self, sequence: Sequence) -> Builder {
         self.push_int(sequence.to_consensus_u32().into())
@@ -137,7 +235,31 @@ impl From<Vec<u8>> for Builder {
     fn from(v: Vec<u8>) -> Builder {
         let script = ScriptBuf::from(v);
         let last_op = script.last_opcode();
-        Builder(script, last_op)
+        let mut open = Vec::new();
+        for instruction in script.instructions().flatten() {
+            if let Instruction::Op(op) = instruction {
+                track_conditional(&mut open, op);
+            }
+        }
+        Builder(script, last_op, open)
+    }
+}
+
+/// Updates the conditionals left `open` by a script, each with whether it already has an
+/// `OP_ELSE`, after `opcode` is appended to it.
+///
+/// Opcodes that do not fit the conditional structure leave `open` unchanged.
+fn track_conditional(open: &mut Vec<bool>, opcode: Opcode) {
+    match opcode {
+        OP_IF | OP_NOTIF => open.push(false),
+        OP_ELSE =>
+            if let Some(has_else) = open.last_mut() {
+                *has_else = true;
+            },
+        OP_ENDIF => {
+            open.pop();
+        }
+        _ => {}
     }
 }
 
@@ -146,3 +268,47 @@ impl fmt::Display for Builder {
 }
 
 internals::debug_from_display!(Builder);
+
+/// An error building a script with the checked [`Builder`] methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuilderError {
+    /// The opcode does not fit the conditional structure of the script, e.g. an `OP_ELSE` without
+    /// a preceding `OP_IF`.
+    UnbalancedControlFlow {
+        /// The offending opcode.
+        opcode: Opcode,
+    },
+    /// The data to push is larger than [`MAX_SCRIPT_ELEMENT_SIZE`].
+    ///
+    /// [`MAX_SCRIPT_ELEMENT_SIZE`]: crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+    Oversize(OversizeError),
+}
+
+impl fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use BuilderError::*;
+
+        match *self {
+            UnbalancedControlFlow { opcode } =>
+                write!(f, "{} does not match the conditional structure of the script", opcode),
+            Oversize(ref e) => write_err!(f, "invalid data push"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuilderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use BuilderError::*;
+
+        match *self {
+            UnbalancedControlFlow { .. } => None,
+            Oversize(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<OversizeError> for BuilderError {
+    fn from(e: OversizeError) -> Self { Self::Oversize(e) }
+}
@@ -56,6 +56,7 @@ use core::fmt;
 use core::ops::{Deref, DerefMut};
 
 use hashes::{hash160, sha256};
+use internals::write_err;
 #[cfg(feature = "serde")]
 use serde;
 
@@ -727,6 +728,78 @@ impl std::error::Error for Error {
     }
 }
 
+/// A scriptSig is not push only, see [`Script::check_sigpushonly`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SigPushOnlyError {
+    /// The script contains a non-push opcode.
+    NonPushOpcode {
+        /// Byte offset of the opcode in the script.
+        index: usize,
+        /// The offending opcode.
+        opcode: Opcode,
+    },
+    /// The script could not be parsed.
+    Script(Error),
+}
+
+impl fmt::Display for SigPushOnlyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SigPushOnlyError::*;
+
+        match *self {
+            NonPushOpcode { index, opcode } =>
+                write!(f, "non-push opcode {} at offset {} in scriptSig", opcode, index),
+            Script(ref e) => write_err!(f, "invalid scriptSig"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SigPushOnlyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use SigPushOnlyError::*;
+
+        match *self {
+            NonPushOpcode { .. } => None,
+            Script(ref e) => Some(e),
+        }
+    }
+}
+
+/// An error creating a script with [`ScriptBuf::from_hex_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HexScriptError {
+    /// The string is not valid hex.
+    InvalidHex(hex::HexToBytesError),
+    /// The bytes do not parse as a script, e.g. a push runs past the end.
+    InvalidOpcodes(Error),
+}
+
+impl fmt::Display for HexScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use HexScriptError::*;
+
+        match *self {
+            InvalidHex(ref e) => write_err!(f, "invalid script hex"; e),
+            InvalidOpcodes(ref e) => write_err!(f, "invalid script"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HexScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use HexScriptError::*;
+
+        match *self {
+            InvalidHex(ref e) => Some(e),
+            InvalidOpcodes(ref e) => Some(e),
+        }
+    }
+}
+
 // Our internal error proves that we only return these two cases from `read_uint_iter`.
 // Since it's private we don't bother with trait impls besides From.
 enum UintError {
@@ -1,16 +1,19 @@
 // SPDX-License-Identifier: CC0-1.0
 
+use core::fmt;
 #[cfg(doc)]
 use core::ops::Deref;
 
 use secp256k1::{Secp256k1, Verification};
 
+use crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE;
 use crate::blockdata::opcodes::all::*;
 use crate::blockdata::opcodes::{self, Opcode};
 use crate::blockdata::script::witness_program::WitnessProgram;
 use crate::blockdata::script::witness_version::WitnessVersion;
 use crate::blockdata::script::{
-    opcode_to_verify, Builder, Instruction, PushBytes, Script, ScriptHash, WScriptHash,
+    opcode_to_verify, Builder, HexScriptError, Instruction, PushBytes, Script, ScriptHash,
+    WScriptHash,
 };
 use crate::key::{
     PubkeyHash, PublicKey, TapTweak, TweakedPublicKey, UntweakedPublicKey, WPubkeyHash,
@@ -74,28 +77,65 @@ impl ScriptBuf {
     pub fn builder() -> Builder { Builder::new() }
 
     /// Generates P2PK-type of scriptPubkey.
+    ///
+    /// P2PK is not recommended for new outputs, use [`ScriptBuf::new_p2pkh`] or a segwit type
+    /// instead. It is still needed for legacy outputs such as the genesis coinbase.
+    #[doc(alias = "from_public_key")]
     pub fn new_p2pk(pubkey: &PublicKey) -> Self {
         Builder::new().push_key(pubkey).push_opcode(OP_CHECKSIG).into_script()
     }
 
     /// Generates P2PKH-type of scriptPubkey.
     pub fn new_p2pkh(pubkey_hash: &PubkeyHash) -> Self {
-        Builder::new()
-            .push_opcode(OP_DUP)
-            .push_opcode(OP_HASH160)
-            .push_slice(pubkey_hash)
-            .push_opcode(OP_EQUALVERIFY)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+        Builder::new().push_p2pkh(pubkey_hash).into_script()
     }
 
     /// Generates P2SH-type of scriptPubkey with a given hash of the redeem script.
     pub fn new_p2sh(script_hash: &ScriptHash) -> Self {
-        Builder::new()
-            .push_opcode(OP_HASH160)
-            .push_slice(script_hash)
-            .push_opcode(OP_EQUAL)
-            .into_script()
+        Builder::new().push_p2sh(script_hash).into_script()
+    }
+
+    /// Generates a bare multisig redeem script and the P2SH scriptPubkey paying to it.
+    ///
+    /// The redeem script is of the form `<threshold> <pubkey>... <n> OP_CHECKMULTISIG` with the
+    /// keys pushed in the order given. Returns `(redeem_script, script_pubkey)`.
+    ///
+    /// # Errors
+    ///
+    /// If `threshold` is zero or greater than the number of keys, if more than 16 keys are given or
+    /// if the redeem script would exceed the 520 byte P2SH limit.
+    pub fn new_p2sh_multisig(
+        threshold: usize,
+        pubkeys: &[PublicKey],
+    ) -> Result<(Self, Self), MultisigError> {
+        if pubkeys.len() > 16 {
+            return Err(MultisigError::TooManyKeys(pubkeys.len()));
+        }
+        if threshold == 0 || threshold > pubkeys.len() {
+            return Err(MultisigError::InvalidThreshold { threshold, keys: pubkeys.len() });
+        }
+
+        let mut builder = Builder::new().push_int(threshold as i64);
+        for pubkey in pubkeys {
+            builder = builder.push_key(pubkey);
+        }
+        let redeem_script =
+            builder.push_int(pubkeys.len() as i64).push_opcode(OP_CHECKMULTISIG).into_script();
+
+        if redeem_script.len() > MAX_SCRIPT_ELEMENT_SIZE {
+            return Err(MultisigError::ExcessiveScriptSize(redeem_script.len()));
+        }
+        let script_pubkey = redeem_script.to_p2sh();
+        Ok((redeem_script, script_pubkey))
+    }
+
+    /// Generates a P2WPKH redeem script and the P2SH scriptPubkey wrapping it (P2SH-P2WPKH).
+    ///
+    /// Returns `(redeem_script, script_pubkey)` or `None` if `pubkey` is uncompressed.
+    pub fn new_p2sh_p2wpkh(pubkey: &PublicKey) -> Option<(Self, Self)> {
+        let redeem_script = Self::new_p2wpkh(&pubkey.wpubkey_hash()?);
+        let script_pubkey = redeem_script.to_p2sh();
+        Some((redeem_script, script_pubkey))
     }
 
     /// Generates P2WPKH-type of scriptPubkey.
@@ -189,11 +229,28 @@ impl ScriptBuf {
         Ok(ScriptBuf::from_bytes(v))
     }
 
+    /// Creates a [`ScriptBuf`] from a hex string, checking that the bytes parse as a script.
+    ///
+    /// Unlike [`ScriptBuf::from_hex`] this rejects scripts ending in the middle of a push.
+    pub fn from_hex_checked(s: &str) -> Result<Self, HexScriptError> {
+        let script = ScriptBuf::from_hex(s).map_err(HexScriptError::InvalidHex)?;
+        if let Some(Err(e)) = script.instructions().find(Result::is_err) {
+            return Err(HexScriptError::InvalidOpcodes(e));
+        }
+        Ok(script)
+    }
+
     /// Converts byte vector into script.
     ///
     /// This method doesn't (re)allocate.
     pub fn from_bytes(bytes: Vec<u8>) -> Self { ScriptBuf(bytes) }
 
+    /// Creates an owned copy of `script`.
+    ///
+    /// This is the same as `script.to_owned()`, spelled out for clarity.
+    #[doc(alias = "clone_as_script_buf")]
+    pub fn from_script(script: &Script) -> Self { ScriptBuf(script.as_bytes().to_vec()) }
+
     /// Converts the script into a byte vector.
     ///
     /// This method doesn't (re)allocate.
@@ -361,3 +418,45 @@ impl<'a> Extend<Instruction<'a>> for ScriptBuf {
         }
     }
 }
+
+/// An error constructing a multisig script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MultisigError {
+    /// The threshold is zero or greater than the number of keys.
+    InvalidThreshold {
+        /// The requested number of signatures.
+        threshold: usize,
+        /// The number of public keys.
+        keys: usize,
+    },
+    /// More than 16 public keys were provided.
+    TooManyKeys(usize),
+    /// The redeem script is larger than 520 bytes.
+    ExcessiveScriptSize(usize),
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use MultisigError::*;
+
+        match *self {
+            InvalidThreshold { threshold, keys } =>
+                write!(f, "invalid multisig threshold {} for {} keys", threshold, keys),
+            TooManyKeys(n) => write!(f, "too many multisig keys: {} (max 16)", n),
+            ExcessiveScriptSize(n) =>
+                write!(f, "multisig redeem script too large: {} bytes (max 520)", n),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MultisigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use MultisigError::*;
+
+        match *self {
+            InvalidThreshold { .. } | TooManyKeys(_) | ExcessiveScriptSize(_) => None,
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //! Contains `PushBytes` & co
 
 use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
 use core::ops::{Deref, DerefMut};
 
 pub use primitive::*;
@@ -20,7 +21,8 @@ mod primitive {
         Index, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
     };
 
-    use super::PushBytesError;
+    use super::{OversizeError, PushBytesError};
+    use crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE;
     #[allow(unused)]
     use crate::prelude::*;
 
@@ -70,6 +72,20 @@ mod primitive {
             unsafe { Self::from_slice_unchecked(&[]) }
         }
 
+        /// Creates `&Self` from a data push that is valid under the consensus rules.
+        ///
+        /// ## Errors
+        ///
+        /// This method fails if `bytes` is longer than [`MAX_SCRIPT_ELEMENT_SIZE`] (520 bytes),
+        /// scripts pushing such elements fail to execute.
+        pub fn new(bytes: &[u8]) -> Result<&Self, OversizeError> {
+            if bytes.len() > MAX_SCRIPT_ELEMENT_SIZE {
+                return Err(OversizeError { len: bytes.len() });
+            }
+            // MAX_SCRIPT_ELEMENT_SIZE < LIMIT
+            Ok(unsafe { Self::from_slice_unchecked(bytes) })
+        }
+
         /// Returns the underlying bytes.
         pub fn as_bytes(&self) -> &[u8] { &self.0 }
 
@@ -417,3 +433,29 @@ mod error {
 impl std::error::Error for PushBytesError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
 }
+
+/// Error returned by [`PushBytes::new`] when the data exceeds [`MAX_SCRIPT_ELEMENT_SIZE`].
+///
+/// [`MAX_SCRIPT_ELEMENT_SIZE`]: crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OversizeError {
+    /// How long the input was.
+    pub len: usize,
+}
+
+impl fmt::Display for OversizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "attempt to push {} bytes into script but the element size limit is {}",
+            self.len,
+            crate::blockdata::constants::MAX_SCRIPT_ELEMENT_SIZE
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OversizeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+}
@@ -47,6 +47,24 @@ fn script() {
     script = script.push_opcode(OP_CHECKSIG); comp.push(0xACu8); assert_eq!(script.as_bytes(), &comp[..]);
 }
 
+#[test]
+fn opcode_for_number() {
+    assert_eq!(Script::opcode_for_number(-1), Some(OP_PUSHNUM_NEG1));
+    assert_eq!(Script::opcode_for_number(0), Some(OP_PUSHBYTES_0));
+    assert_eq!(Script::opcode_for_number(1), Some(OP_PUSHNUM_1));
+    assert_eq!(Script::opcode_for_number(16), Some(OP_PUSHNUM_16));
+    assert_eq!(Script::opcode_for_number(17), None);
+    assert_eq!(Script::opcode_for_number(-2), None);
+
+    for n in -1..=16 {
+        let opcode = Script::opcode_for_number(n).unwrap();
+        assert_eq!(Builder::new().push_int(n).as_bytes(), &[opcode.to_u8()]);
+    }
+    // Numbers without a dedicated opcode must use a data push.
+    assert_eq!(Builder::new().push_int(17).as_bytes(), &[0x01, 0x11]);
+    assert_eq!(Builder::new().push_int(-2).as_bytes(), &[0x01, 0x82]);
+}
+
 #[test]
 fn p2pk_pubkey_bytes_valid_key_and_valid_script_returns_expected_key() {
     let key_str = "0411db93e1dcdb8a016b49840f8c53bc1eb68a382e97b1482ecad7b148a6909a5cb2e0eaddfb84ccf9744464f82e160bfa9b8b64f9d4c03f999b8643f656b412a3";
@@ -197,6 +215,114 @@ fn script_builder() {
     assert_eq!(script.to_hex_string(), "76a91416e1ae70ff0fa102905d4af297f6912bda6cce1988ac");
 }
 
+#[test]
+fn script_builder_p2pkh_p2sh() {
+    let pubkey_hash = PubkeyHash::from_str("16e1ae70ff0fa102905d4af297f6912bda6cce19").unwrap();
+    let manual = Builder::new()
+        .push_opcode(OP_DUP)
+        .push_opcode(OP_HASH160)
+        .push_slice(&pubkey_hash)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+    let script = Builder::new().push_p2pkh(&pubkey_hash).into_script();
+    assert_eq!(script, manual);
+    assert_eq!(script.to_hex_string(), "76a91416e1ae70ff0fa102905d4af297f6912bda6cce1988ac");
+
+    let script_hash = ScriptHash::from_str("16e1ae70ff0fa102905d4af297f6912bda6cce19").unwrap();
+    let manual = Builder::new()
+        .push_opcode(OP_HASH160)
+        .push_slice(&script_hash)
+        .push_opcode(OP_EQUAL)
+        .into_script();
+    let script = Builder::new().push_p2sh(&script_hash).into_script();
+    assert_eq!(script, manual);
+    assert!(script.is_p2sh());
+}
+
+#[test]
+fn push_bytes_element_size() {
+    let max = [0xabu8; 520];
+    let push = PushBytes::new(&max).unwrap();
+    assert_eq!(push.len(), 520);
+    let script = Builder::new().push_slice(push).into_script();
+    assert_eq!(script.len(), 523);
+    assert_eq!(script.instructions().next(), Some(Ok(Instruction::PushBytes(push))));
+
+    let over = [0xabu8; 521];
+    let err = PushBytes::new(&over).unwrap_err();
+    assert_eq!(err.len, 521);
+
+    let script = Builder::new().push_slice_unchecked(&over).into_script();
+    assert_eq!(script.len(), 524);
+    assert_eq!(&script.as_bytes()[..3], &[0x4d, 0x09, 0x02]);
+}
+
+#[test]
+fn script_builder_push_str() {
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let message = "Six Flags coaster victim concerned about seat. USAToday - 07.20.2013";
+    let script = Builder::new()
+        .push_slice([0xff, 0xff, 0x00, 0x1d])
+        .push_slice([0x04])
+        .push_str(message)
+        .unwrap()
+        .into_script();
+    assert_eq!(script, genesis_block(Network::Bitcoin).txdata[0].input[0].script_sig);
+
+    let long = "a".repeat(521);
+    match Builder::new().push_str(&long) {
+        Err(BuilderError::Oversize(e)) => assert_eq!(e.len, 521),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(Builder::new().push_str(&long[1..]).unwrap().len(), 523);
+}
+
+#[test]
+fn script_builder_push_data() {
+    let data = [0xab; 20];
+    let expected = Builder::new().push_slice(data).into_script();
+    assert_eq!(Builder::new().push_data(&data).into_script(), expected);
+    let push = PushBytes::new(&data).unwrap();
+    assert_eq!(Builder::new().push_bytes_ref(push).into_script(), expected);
+
+    let over = [0xab; 521];
+    let script = Builder::new().push_data(&over).into_script();
+    assert_eq!(script, Builder::new().push_slice_unchecked(&over).into_script());
+    assert_eq!(script.len(), 524);
+}
+
+#[test]
+fn script_builder_lock_times() {
+    use crate::blockdata::locktime::absolute;
+    use crate::blockdata::transaction::Sequence;
+
+    for &n in &[0, 16, 500_000, 1_700_000_000] {
+        let lock_time = absolute::LockTime::from_consensus(n);
+        let script = Builder::new()
+            .push_lock_time(lock_time)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_DROP)
+            .into_script();
+        let mut instructions = script.instructions();
+        let pushed = instructions.next().unwrap().unwrap().script_num().unwrap();
+        assert_eq!(absolute::LockTime::from_consensus(pushed as u32), lock_time);
+        assert_eq!(instructions.next(), Some(Ok(Instruction::Op(OP_CLTV))));
+    }
+
+    for &sequence in &[Sequence::from_height(144), Sequence::from_512_second_intervals(10)] {
+        let lock_time = sequence.to_relative_lock_time().unwrap();
+        let script =
+            Builder::new().push_relative_lock_time(lock_time).push_opcode(OP_CSV).into_script();
+        let expected = Builder::new().push_sequence(sequence).push_opcode(OP_CSV).into_script();
+        assert_eq!(script, expected);
+        let pushed = script.instructions().next().unwrap().unwrap().script_num().unwrap();
+        assert_eq!(Sequence(pushed as u32).to_relative_lock_time(), Some(lock_time));
+    }
+}
+
 #[test]
 fn script_generators() {
     let pubkey =
@@ -232,6 +358,55 @@ fn script_generators() {
     );
 }
 
+#[test]
+fn p2sh_multisig_generator() {
+    let pubkeys = [
+        "02632b12f4ac5b1d1b72b2a3b508c19172de44f6f46bcee50ba33f3f9291e47ed0",
+        "027735a29bae7780a9755fae7a1c4374c656ac6a69ea9f3697fda61bb99a4f3e77",
+        "02e2cc6bd5f45edd43bebe7cb9b675f0ce9ed3efe613b177588290ad188d11b404",
+    ]
+    .iter()
+    .map(|s| PublicKey::from_str(s).unwrap())
+    .collect::<Vec<_>>();
+
+    let (redeem_script, script_pubkey) = ScriptBuf::new_p2sh_multisig(2, &pubkeys).unwrap();
+    assert!(redeem_script.is_multisig());
+    assert_eq!(redeem_script.len(), 1 + 3 * 34 + 1 + 1);
+    assert_eq!(script_pubkey.first_opcode(), Some(OP_HASH160));
+    assert!(script_pubkey.is_p2sh());
+    assert_eq!(script_pubkey, redeem_script.to_p2sh());
+
+    assert_eq!(
+        ScriptBuf::new_p2sh_multisig(0, &pubkeys),
+        Err(MultisigError::InvalidThreshold { threshold: 0, keys: 3 })
+    );
+    assert_eq!(
+        ScriptBuf::new_p2sh_multisig(4, &pubkeys),
+        Err(MultisigError::InvalidThreshold { threshold: 4, keys: 3 })
+    );
+    assert_eq!(
+        ScriptBuf::new_p2sh_multisig(1, &[pubkeys[0]; 17]),
+        Err(MultisigError::TooManyKeys(17))
+    );
+}
+
+#[test]
+fn p2sh_p2wpkh_generator() {
+    let pubkey =
+        PublicKey::from_str("0234e6a79c5359c613762d537e0e19d86c77c1666d8c9ab050f23acd198e97f93e")
+            .unwrap();
+
+    let (redeem_script, script_pubkey) = ScriptBuf::new_p2sh_p2wpkh(&pubkey).unwrap();
+    assert!(redeem_script.is_p2wpkh());
+    assert_eq!(redeem_script, ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash().unwrap()));
+    assert_eq!(script_pubkey.first_opcode(), Some(OP_HASH160));
+    assert!(script_pubkey.is_p2sh());
+    assert_eq!(script_pubkey, redeem_script.to_p2sh());
+
+    let uncompressed = PublicKey { compressed: false, inner: pubkey.inner };
+    assert_eq!(ScriptBuf::new_p2sh_p2wpkh(&uncompressed), None);
+}
+
 #[test]
 fn script_builder_verify() {
     let simple = Builder::new().push_verify().into_script();
@@ -342,6 +517,25 @@ fn script_hashes() {
         script.wscript_hash().to_string(),
         "3e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324"
     );
+
+    let p2sh = ScriptBuf::new_p2sh(&script.script_hash());
+    assert!(p2sh.is_p2sh());
+    assert_eq!(p2sh.to_hex_string(), "a9148292bcfbef1884f73c813dfe9c82fd7e814291ea87");
+    let p2wsh = ScriptBuf::new_p2wsh(&script.wscript_hash());
+    assert!(p2wsh.is_p2wsh());
+    assert_eq!(
+        p2wsh.to_hex_string(),
+        "00203e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324"
+    );
+    assert_eq!(
+        ScriptHash::from_str("8292bcfbef1884f73c813dfe9c82fd7e814291ea").unwrap(),
+        script.script_hash()
+    );
+    assert_eq!(
+        WScriptHash::from_str("3e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324")
+            .unwrap(),
+        script.wscript_hash()
+    );
     assert_eq!(
         ScriptBuf::from_hex("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")
             .unwrap()
@@ -556,6 +750,86 @@ macro_rules! unwrap_all {
     }
 }
 
+#[test]
+fn instruction_indices_multisig() {
+    let pubkeys = [
+        "02632b12f4ac5b1d1b72b2a3b508c19172de44f6f46bcee50ba33f3f9291e47ed0",
+        "027735a29bae7780a9755fae7a1c4374c656ac6a69ea9f3697fda61bb99a4f3e77",
+        "02e2cc6bd5f45edd43bebe7cb9b675f0ce9ed3efe613b177588290ad188d11b404",
+    ];
+    let mut builder = Builder::new().push_int(2);
+    for key in pubkeys.iter() {
+        builder = builder.push_key(&PublicKey::from_str(key).unwrap());
+    }
+    let script = builder.push_int(3).push_opcode(OP_CHECKMULTISIG).into_script();
+
+    let offsets = script
+        .instruction_indices()
+        .map(|item| item.map(|(offset, _)| offset))
+        .collect::<Result<Vec<_>, Error>>()
+        .unwrap();
+    // Each key push is one length byte followed by 33 bytes of key.
+    assert_eq!(offsets, vec![0, 1, 35, 69, 103, 104]);
+
+    for (offset, instruction) in script.instruction_indices().map(Result::unwrap) {
+        if let Instruction::PushBytes(bytes) = instruction {
+            assert_eq!(&script.as_bytes()[offset + 1..offset + 34], bytes.as_bytes());
+        }
+    }
+}
+
+#[test]
+fn push_only() {
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let genesis = genesis_block(Network::Bitcoin);
+    assert!(genesis.txdata[0].input[0].script_sig.is_push_only());
+
+    // scriptSig of the P2PKH spend in the transaction tests: <sig> <pubkey>
+    let p2pkh_sig = ScriptBuf::from_hex("493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52").unwrap();
+    assert!(p2pkh_sig.is_push_only());
+
+    let script = Builder::new().push_int(-1).push_int(16).push_slice([0xab; 73]).into_script();
+    assert!(script.is_push_only());
+
+    let with_dup = Builder::new().push_slice([1u8, 2, 3]).push_opcode(OP_DUP).into_script();
+    assert!(!with_dup.is_push_only());
+    // A truncated push is not a push.
+    assert!(!ScriptBuf::from_hex("4c02ab").unwrap().is_push_only());
+}
+
+#[test]
+fn check_sigpushonly() {
+    // scriptSig of the P2PKH spend in the transaction tests: <sig> <pubkey>
+    let p2pkh_sig = ScriptBuf::from_hex("493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52").unwrap();
+    assert_eq!(p2pkh_sig.check_sigpushonly(), Ok(()));
+    let pushnum = Builder::new().push_int(-1).push_int(16).into_script();
+    assert_eq!(pushnum.check_sigpushonly(), Ok(()));
+
+    let reserved = Builder::new().push_int(16).push_opcode(OP_RESERVED).into_script();
+    assert!(reserved.is_push_only());
+    assert_eq!(
+        reserved.check_sigpushonly(),
+        Err(SigPushOnlyError::NonPushOpcode { index: 1, opcode: OP_RESERVED })
+    );
+
+    let with_dup = Builder::new().push_slice([1u8, 2, 3]).push_opcode(OP_DUP).into_script();
+    assert_eq!(
+        with_dup.check_sigpushonly(),
+        Err(SigPushOnlyError::NonPushOpcode { index: 4, opcode: OP_DUP })
+    );
+    let with_nop = Builder::new().push_opcode(OP_NOP).into_script();
+    assert_eq!(
+        with_nop.check_sigpushonly(),
+        Err(SigPushOnlyError::NonPushOpcode { index: 0, opcode: OP_NOP })
+    );
+    assert_eq!(
+        ScriptBuf::from_hex("4c02ab").unwrap().check_sigpushonly(),
+        Err(SigPushOnlyError::Script(Error::EarlyEndOfScript))
+    );
+}
+
 #[test]
 fn test_iterator() {
     let zero = ScriptBuf::from_hex("00").unwrap();
@@ -738,6 +1012,52 @@ fn test_script_get_sigop_count() {
     assert_eq!(multi_nopushnum_op.count_sigops_legacy(), 20);
 }
 
+#[test]
+fn script_sigop_count_witness() {
+    let p2wpkh = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+    assert_eq!(p2wpkh.count_sigops_witness(None), 1);
+
+    let multi = Builder::new()
+        .push_opcode(OP_PUSHNUM_1)
+        .push_slice([3; 33])
+        .push_slice([3; 33])
+        .push_slice([3; 33])
+        .push_opcode(OP_PUSHNUM_3)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    let p2wsh = multi.to_p2wsh();
+    assert_eq!(p2wsh.count_sigops_witness(Some(&multi)), 3);
+    assert_eq!(p2wsh.count_sigops_witness(None), 0);
+
+    // Witness sigops only apply to witness programs.
+    assert_eq!(multi.count_sigops_witness(Some(&multi)), 0);
+}
+
+#[test]
+fn nested_witness_script() {
+    use crate::blockdata::script::witness_version::WitnessVersion;
+
+    let p2wpkh = ScriptBuf::from_hex("00141c6977423aa4b82a0d7f8496cdf3fc2f8b4f580c").unwrap();
+    let p2wsh =
+        ScriptBuf::from_hex("00203e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324")
+            .unwrap();
+    let p2tr =
+        ScriptBuf::from_hex("51203e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324")
+            .unwrap();
+    let p2pkh = ScriptBuf::from_hex("76a91416e1ae70ff0fa102905d4af297f6912bda6cce1988ac").unwrap();
+
+    assert!(p2wpkh.is_witness_script());
+    assert_eq!(p2wpkh.witness_version(), Some(WitnessVersion::V0));
+    assert!(p2wsh.is_witness_script());
+    assert_eq!(p2wsh.witness_version(), Some(WitnessVersion::V0));
+    assert!(!p2tr.is_witness_script());
+    assert_eq!(p2tr.witness_version(), Some(WitnessVersion::V1));
+    assert!(!p2pkh.is_witness_script());
+    assert_eq!(p2pkh.witness_version(), None);
+    // The wrapping P2SH output itself is not a witness program.
+    assert!(!p2wpkh.to_p2sh().is_witness_script());
+}
+
 #[test]
 #[cfg(feature = "serde")]
 fn test_script_serde_human_and_not() {
@@ -871,3 +1191,216 @@ fn instruction_script_num_parse() {
         Some(Ok(Instruction::PushBytes(PushBytes::empty()))),
     );
 }
+
+#[test]
+fn v0_witness_programs() {
+    let p2wpkh = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+    assert_eq!(
+        p2wpkh.v0_p2wpkh_program(),
+        Some(&hex!("751e76e8199196d454941c45d1b3a323f1433bd6")[..])
+    );
+    assert_eq!(p2wpkh.v0_p2wsh_program(), None);
+
+    let p2wsh =
+        ScriptBuf::from_hex("00203e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324")
+            .unwrap();
+    assert_eq!(
+        p2wsh.v0_p2wsh_program(),
+        Some(&hex!("3e1525eb183ad4f9b3c5fa3175bdca2a52e947b135bbb90383bf9f6408e2c324")[..])
+    );
+    assert_eq!(p2wsh.v0_p2wpkh_program(), None);
+
+    // Same length and push but not segwit v0.
+    let p2tr_like = ScriptBuf::from_hex("5114751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+    assert_eq!(p2tr_like.v0_p2wpkh_program(), None);
+    let p2pkh = ScriptBuf::from_hex("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac").unwrap();
+    assert_eq!(p2pkh.v0_p2wpkh_program(), None);
+    assert_eq!(p2pkh.v0_p2wsh_program(), None);
+}
+
+#[test]
+fn script_buf_from_script() {
+    use core::borrow::Borrow;
+
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let genesis = genesis_block(Network::Bitcoin);
+    let script_pubkey = &genesis.txdata[0].output[0].script_pubkey;
+    assert_eq!(ScriptBuf::from_script(script_pubkey), *script_pubkey);
+
+    // Coercions in both directions without copying.
+    let script: &Script = script_pubkey;
+    let borrowed: &Script = script_pubkey.borrow();
+    assert_eq!(script.as_bytes().as_ptr(), script_pubkey.as_bytes().as_ptr());
+    assert_eq!(borrowed.as_bytes().as_ptr(), script_pubkey.as_bytes().as_ptr());
+    assert_eq!(script.to_owned(), ScriptBuf::from_script(script));
+}
+
+#[test]
+fn script_builder_push_opcode_checked() {
+    use crate::blockdata::opcodes::all::*;
+
+    let script = Builder::new()
+        .push_opcode_checked(OP_IF)
+        .unwrap()
+        .push_int(1)
+        .push_opcode_checked(OP_ENDIF)
+        .unwrap()
+        .into_script();
+    assert_eq!(script.as_bytes(), &[0x63, 0x51, 0x68]);
+
+    let nested = Builder::new()
+        .push_opcode_checked(OP_NOTIF)
+        .and_then(|b| b.push_opcode_checked(OP_IF))
+        .and_then(|b| b.push_opcode_checked(OP_ELSE))
+        .and_then(|b| b.push_opcode_checked(OP_ENDIF))
+        .and_then(|b| b.push_opcode_checked(OP_ELSE))
+        .and_then(|b| b.push_opcode_checked(OP_ENDIF));
+    assert!(nested.is_ok());
+
+    assert_eq!(
+        Builder::new().push_opcode_checked(OP_ELSE),
+        Err(BuilderError::UnbalancedControlFlow { opcode: OP_ELSE })
+    );
+    assert_eq!(
+        Builder::new().push_int(1).push_opcode_checked(OP_ENDIF),
+        Err(BuilderError::UnbalancedControlFlow { opcode: OP_ENDIF })
+    );
+    let with_else = Builder::new().push_opcode(OP_IF).push_opcode(OP_ELSE);
+    assert_eq!(
+        with_else.push_opcode_checked(OP_ELSE),
+        Err(BuilderError::UnbalancedControlFlow { opcode: OP_ELSE })
+    );
+
+    // Conditionals opened by an existing script are picked up, an `OP_IF` byte inside a data
+    // push is not a conditional.
+    let from_bytes = Builder::from(vec![0x63, 0x51, 0x67]);
+    assert_eq!(
+        from_bytes.clone().push_opcode_checked(OP_ELSE),
+        Err(BuilderError::UnbalancedControlFlow { opcode: OP_ELSE })
+    );
+    assert!(from_bytes.push_opcode_checked(OP_ENDIF).is_ok());
+    assert!(Builder::new().push_slice([0x63]).push_opcode_checked(OP_ENDIF).is_err());
+}
+
+#[test]
+fn script_from_hex_checked() {
+    let p2pkh = "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac";
+    assert_eq!(ScriptBuf::from_hex_checked(p2pkh).unwrap(), ScriptBuf::from_hex(p2pkh).unwrap());
+    assert_eq!(ScriptBuf::from_hex_checked("").unwrap(), ScriptBuf::new());
+
+    // OP_PUSHDATA1 of two bytes with only one present.
+    assert_eq!(ScriptBuf::from_hex("4c02ab").unwrap().len(), 3);
+    assert_eq!(
+        ScriptBuf::from_hex_checked("4c02ab"),
+        Err(HexScriptError::InvalidOpcodes(Error::EarlyEndOfScript))
+    );
+    assert!(matches!(ScriptBuf::from_hex_checked("76a9zz"), Err(HexScriptError::InvalidHex(_))));
+    assert!(matches!(ScriptBuf::from_hex_checked("76a"), Err(HexScriptError::InvalidHex(_))));
+}
+
+#[test]
+fn genesis_script_pubkey_bytes() {
+    use hex::FromHex;
+
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let genesis = genesis_block(Network::Bitcoin);
+    let script_pubkey = &genesis.txdata[0].output[0].script_pubkey;
+    let expected = "41040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc8bc4a8e4d3eb4b10f4d4604fa08dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9ac";
+    assert_eq!(script_pubkey.as_bytes(), &Vec::<u8>::from_hex(expected).unwrap()[..]);
+    assert_eq!(script_pubkey.to_hex_string(), expected);
+    assert_eq!(script_pubkey.len(), 67);
+    assert!(!script_pubkey.is_empty());
+    assert!(Script::new().is_empty());
+}
+
+#[test]
+fn genesis_p2pk_public_key() {
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let genesis = genesis_block(Network::Bitcoin);
+    let script_pubkey = &genesis.txdata[0].output[0].script_pubkey;
+    assert!(script_pubkey.is_p2pk());
+
+    // The key is the 65 byte push between the push opcode and `OP_CHECKSIG`.
+    let key = script_pubkey.p2pk_public_key().unwrap();
+    assert!(!key.compressed);
+    assert_eq!(key.to_bytes(), &script_pubkey.as_bytes()[1..66]);
+
+    assert_eq!(ScriptBuf::new_p2pk(&key), *script_pubkey);
+
+    let p2pkh = ScriptBuf::new_p2pkh(&key.pubkey_hash());
+    assert!(!p2pkh.is_p2pk());
+    assert_eq!(p2pkh.p2pk_public_key(), None);
+}
+
+#[test]
+fn new_p2pk_genesis_output() {
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    let genesis = genesis_block(Network::Bitcoin);
+    let script_pubkey = &genesis.txdata[0].output[0].script_pubkey;
+    let key = PublicKey::from_slice(&script_pubkey.as_bytes()[1..66]).unwrap();
+    let script = ScriptBuf::new_p2pk(&key);
+    assert_eq!(script, *script_pubkey);
+    assert!(script.is_p2pk());
+
+    let compressed = PublicKey::new(key.inner);
+    let script = ScriptBuf::new_p2pk(&compressed);
+    let mut expected = vec![0x21];
+    expected.extend_from_slice(&key.inner.serialize());
+    expected.push(0xac);
+    assert_eq!(script.as_bytes(), &expected[..]);
+    assert_eq!(script.p2pk_public_key(), Some(compressed));
+}
+
+#[test]
+fn multisig_to_p2sh_and_p2wsh() {
+    // 2-of-2 from `multisig`.
+    let multisig_script = ScriptBuf::from_hex("5221021c4ac2ecebc398e390e07f045aac5cc421f82f0739c1ce724d3d53964dc6537d21023a2e9155e0b62f76737605504819a2b4e5ce20653f6c397d7a178ae42ba702f452ae").unwrap();
+
+    let p2sh = multisig_script.to_p2sh();
+    assert!(p2sh.is_p2sh());
+    assert_eq!(&p2sh.as_bytes()[2..22], &hex!("0ef30ed173199e6575645272551e28644760394d"));
+    assert_eq!(&p2sh.as_bytes()[2..22], multisig_script.script_hash().as_byte_array());
+
+    let p2wsh = multisig_script.to_p2wsh();
+    assert!(p2wsh.is_p2wsh());
+    assert_eq!(
+        &p2wsh.as_bytes()[2..],
+        &hex!("d37a4bfa8c8f2f0f7a87cf7d65d92e218e3d6479492d05267732d60b78cb4e40")
+    );
+}
+
+#[test]
+fn push_canonical_int() {
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    for n in [-1, 0, 1, 4, 16, 17, 255, 486604799].iter() {
+        assert_eq!(
+            Builder::new().push_canonical_int(*n).into_script(),
+            Builder::new().push_int(*n).into_script()
+        );
+    }
+    assert_eq!(Builder::new().push_canonical_int(4).as_bytes(), &[0x54]);
+    assert_eq!(Builder::new().push_int_non_minimal(4).as_bytes(), &[0x01, 0x04]);
+
+    // The genesis coinbase pushes 4 with the non-minimal encoding.
+    let genesis = genesis_block(Network::Bitcoin);
+    let script_sig = &genesis.txdata[0].input[0].script_sig;
+    assert_eq!(&script_sig.as_bytes()[..7], &hex!("04ffff001d0104"));
+
+    let message = b"Six Flags coaster victim concerned about seat. USAToday - 07.20.2013";
+    let rebuild = |builder: Builder| builder.push_slice(message).into_script();
+    let non_minimal = rebuild(Builder::new().push_int(486604799).push_int_non_minimal(4));
+    let canonical = rebuild(Builder::new().push_int(486604799).push_canonical_int(4));
+    assert_eq!(non_minimal, *script_sig);
+    assert_ne!(canonical, *script_sig);
+    assert_eq!(canonical.len(), script_sig.len() - 1);
+}
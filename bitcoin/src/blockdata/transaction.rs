@@ -17,24 +17,31 @@ use core::{cmp, fmt, str};
 
 use hashes::{self, sha256d, Hash};
 use internals::write_err;
+use secp256k1::{Message, Secp256k1, Signing};
 
 use super::Weight;
+use crate::blockdata::constants::LEGACY_SIGOP_SCALE;
 use crate::blockdata::locktime::absolute::{self, Height, Time};
 use crate::blockdata::locktime::relative;
-use crate::blockdata::script::{Script, ScriptBuf};
+use crate::blockdata::script::{self, Builder, Instruction, Script, ScriptBuf, SigPushOnlyError};
 use crate::blockdata::witness::Witness;
 #[cfg(feature = "bitcoinconsensus")]
 pub use crate::consensus::validation::TxVerifyError;
 use crate::consensus::{encode, Decodable, Encodable};
+use crate::crypto::ecdsa;
+use crate::crypto::key::{PrivateKey, PublicKey};
 use crate::hash_types::{Txid, Wtxid};
 use crate::internal_macros::impl_consensus_encoding;
+use crate::network::Network;
 use crate::parse::impl_parse_str_from_int_infallible;
+use crate::policy::{MAX_OP_RETURN_RELAY, MAX_STANDARD_SCRIPTSIG_SIZE};
 use crate::prelude::*;
 use crate::script::Push;
 #[cfg(doc)]
-use crate::sighash::{EcdsaSighashType, TapSighashType};
+use crate::sighash::TapSighashType;
+use crate::sighash::{self, EcdsaSighashType, SighashCache};
 use crate::string::FromHexStr;
-use crate::{io, Amount, VarInt};
+use crate::{io, Amount, FeeRate, VarInt};
 
 /// The marker MUST be a 1-byte zero value: 0x00. (BIP-141)
 const SEGWIT_MARKER: u8 = 0x00;
@@ -265,6 +272,29 @@ impl TxIn {
     ///
     /// Total size includes the witness data (for base size see [`Self::base_size`]).
     pub fn total_size(&self) -> usize { self.base_size() + self.witness.size() }
+
+    /// Returns the data pushed by each operation of the scriptSig, e.g. the signature and public
+    /// key of a P2PKH spend.
+    ///
+    /// The OP_PUSHNUM operations yield their number in script number encoding. Any other opcode
+    /// makes the scriptSig not push only and an error is returned.
+    pub fn script_sig_pushes(&self) -> Result<Vec<Vec<u8>>, SigPushOnlyError> {
+        let mut pushes = Vec::new();
+        for instruction in self.script_sig.instruction_indices() {
+            let (index, instruction) = instruction.map_err(SigPushOnlyError::Script)?;
+            match (instruction, instruction.script_num()) {
+                (Instruction::PushBytes(bytes), _) => pushes.push(bytes.as_bytes().to_vec()),
+                (Instruction::Op(_), Some(n)) => {
+                    let mut buf = [0u8; 8];
+                    let len = script::write_scriptint(&mut buf, n);
+                    pushes.push(buf[..len].to_vec());
+                }
+                (Instruction::Op(opcode), None) =>
+                    return Err(SigPushOnlyError::NonPushOpcode { index, opcode }),
+            }
+        }
+        Ok(pushes)
+    }
 }
 
 impl Default for TxIn {
@@ -312,6 +342,12 @@ impl Sequence {
     /// The sequence number that enables replace-by-fee and absolute lock time but
     /// disables relative lock time.
     pub const ENABLE_RBF_NO_LOCKTIME: Self = Sequence(0xFFFFFFFD);
+    /// The sequence number of a final input, one that does not enable absolute lock time,
+    /// relative lock time or replace-by-fee. Same as [`Sequence::MAX`].
+    pub const FINAL: Self = Sequence::MAX;
+    /// The sequence number used by the input of coinbase transactions, including the genesis
+    /// transaction. Same as [`Sequence::MAX`].
+    pub const COINBASE: Self = Sequence::MAX;
 
     /// The number of bytes that a sequence number contributes to the size of a transaction.
     const SIZE: usize = 4; // Serialized length of a u32.
@@ -554,6 +590,50 @@ impl TxOut {
             script_pubkey,
         }
     }
+
+    /// Returns the dust threshold of this output at the given dust relay fee rate.
+    ///
+    /// This is the fee, at `dust_relay_fee`, of the output plus the input that would later spend
+    /// it (148 bytes for non-witness outputs, 67 vbytes for witness ones) as in Core's
+    /// `GetDustThreshold()`. Core's default dust relay fee is [`DUST_RELAY_TX_FEE`] (3 sat/vB).
+    ///
+    /// [`DUST_RELAY_TX_FEE`]: crate::policy::DUST_RELAY_TX_FEE
+    pub fn dust_value(&self, dust_relay_fee: FeeRate) -> Amount {
+        if self.script_pubkey.is_op_return() {
+            return Amount::ZERO;
+        }
+        let spend_size = if self.script_pubkey.is_witness_program() {
+            32 + 4 + 1 + (107 / 4) + 4
+        } else {
+            32 + 4 + 1 + 107 + 4
+        };
+        dust_relay_fee.fee_vb((self.size() + spend_size) as u64).unwrap_or(Amount::MAX)
+    }
+
+    /// Returns true if the value of this output is below its dust threshold at `dust_relay_fee`.
+    ///
+    /// See [`TxOut::dust_value`].
+    pub fn is_dust(&self, dust_relay_fee: FeeRate) -> bool {
+        self.value < self.dust_value(dust_relay_fee)
+    }
+
+    /// Creates a zero-value `OP_RETURN <data>` output.
+    ///
+    /// # Errors
+    ///
+    /// If `data` is longer than the 80 bytes allowed by the default relay policy, see
+    /// [`MAX_OP_RETURN_RELAY`](crate::policy::MAX_OP_RETURN_RELAY).
+    #[doc(alias = "pay_to_op_return")]
+    pub fn new_op_return(data: &[u8]) -> Result<TxOut, OpReturnError> {
+        // The relay limit includes the `OP_RETURN` and `OP_PUSHDATA1 <len>` bytes.
+        const MAX_DATA_LEN: usize = MAX_OP_RETURN_RELAY as usize - 3;
+
+        let data = <&script::PushBytes>::try_from(data)
+            .ok()
+            .filter(|data| data.len() <= MAX_DATA_LEN)
+            .ok_or(OpReturnError::DataTooLong { len: data.len(), max: MAX_DATA_LEN })?;
+        Ok(TxOut { value: Amount::ZERO, script_pubkey: ScriptBuf::new_op_return(data) })
+    }
 }
 
 /// Returns the total number of bytes that this script pubkey would contribute to a transaction.
@@ -772,6 +852,101 @@ impl Transaction {
         size + absolute::LockTime::SIZE
     }
 
+    /// Returns the weight of everything in this transaction except its inputs.
+    ///
+    /// This is the version, the input count, the outputs with their count, the lock time and, if
+    /// the transaction uses the segwit serialization, the marker and flag. Together with
+    /// [`Transaction::input_weight_sum`] this adds up to [`Transaction::weight`].
+    #[doc(alias = "non_input_weight")]
+    pub fn base_weight_without_inputs(&self) -> Weight {
+        let mut size: usize = 4; // Serialized length of a u32 for the version number.
+
+        size += VarInt::from(self.input.len()).size();
+        size += VarInt::from(self.output.len()).size();
+        size += self.output.iter().map(|output| output.size()).sum::<usize>();
+        size += absolute::LockTime::SIZE;
+
+        let mut weight = Weight::from_non_witness_data_size(size as u64);
+        if self.use_segwit_serialization() {
+            // 1 byte for the marker and 1 for the flag.
+            weight += Weight::from_witness_data_size(2);
+        }
+        weight
+    }
+
+    /// Returns the summed weight of the inputs of this transaction.
+    ///
+    /// Inputs are weighed with [`TxIn::segwit_weight`] if the transaction uses the segwit
+    /// serialization and [`TxIn::legacy_weight`] otherwise.
+    pub fn input_weight_sum(&self) -> Weight {
+        let segwit = self.use_segwit_serialization();
+        self.input
+            .iter()
+            .map(|input| if segwit { input.segwit_weight() } else { input.legacy_weight() })
+            .fold(Weight::ZERO, |sum, weight| sum + weight)
+    }
+
+    /// Returns how each part of the transaction contributes to its serialized size.
+    ///
+    /// The sizes add up to [`Transaction::total_size`], see [`TxSizeBreakdown::total`].
+    pub fn size_breakdown(&self) -> TxSizeBreakdown {
+        let segwit = self.use_segwit_serialization();
+        let mut overhead = VarInt::from(self.input.len()).size()
+            + VarInt::from(self.output.len()).size()
+            + self.input.len() * (OutPoint::SIZE + Sequence::SIZE);
+        if segwit {
+            overhead += 2; // 1 byte for the marker and 1 for the flag.
+        }
+
+        let inputs = self
+            .input
+            .iter()
+            .map(|input| InputSize {
+                script_sig: VarInt::from(input.script_sig.len()).size() + input.script_sig.len(),
+                witness: if segwit { input.witness.size() } else { 0 },
+            })
+            .collect();
+        let outputs = self
+            .output
+            .iter()
+            .map(|output| OutputSize {
+                value: Amount::SIZE,
+                script_pubkey: output.size() - Amount::SIZE,
+            })
+            .collect();
+
+        TxSizeBreakdown {
+            version: 4, // Serialized length of a u32 for the version number.
+            inputs,
+            outputs,
+            locktime: absolute::LockTime::SIZE,
+            overhead,
+        }
+    }
+
+    /// Returns `true` if any input has witness data, i.e. the transaction is segwit.
+    ///
+    /// Segwit transactions are serialized as specified in BIP-144 for relay, the [`Txid`] is
+    /// always computed from the legacy serialization (see [`Self::legacy_serialization_size`]).
+    pub fn is_segwit(&self) -> bool { self.input.iter().any(|input| !input.witness.is_empty()) }
+
+    /// Returns the size of this transaction serialized in the BIP-144 (segwit) format.
+    ///
+    /// This includes the marker, the flag and a witness for every input, even if it is empty, so
+    /// it only matches the consensus serialization if the transaction [`is_segwit`].
+    ///
+    /// [`is_segwit`]: Self::is_segwit
+    pub fn segwit_serialization_size(&self) -> usize {
+        let witness_size = self.input.iter().map(|input| input.witness.size()).sum::<usize>();
+        // 1 byte for the marker and 1 for the flag.
+        self.base_size() + 2 + witness_size
+    }
+
+    /// Returns the size of this transaction serialized in the legacy format, without witnesses.
+    ///
+    /// This is the serialization hashed to compute the [`Txid`], same as [`Self::base_size`].
+    pub fn legacy_serialization_size(&self) -> usize { self.base_size() }
+
     /// Returns the "virtual size" (vsize) of this transaction.
     ///
     /// Will be `ceil(weight / 4.0)`. Note this implements the virtual size as per [`BIP141`], which
@@ -808,6 +983,15 @@ impl Transaction {
     #[deprecated(since = "0.31.0", note = "use is_coinbase instead")]
     pub fn is_coin_base(&self) -> bool { self.is_coinbase() }
 
+    /// Returns the first height at which the outputs of this coinbase transaction can be spent.
+    ///
+    /// `mined_height` is the height of the block containing the transaction, see
+    /// [`Network::coinbase_maturity`]. Outputs of non-coinbase transactions are not subject to
+    /// maturity, this does not check whether the transaction is a coinbase.
+    pub fn coinbase_spendable_at(&self, mined_height: u32, network: Network) -> u32 {
+        mined_height.saturating_add(network.coinbase_maturity())
+    }
+
     /// Returns `true` if the transaction itself opted in to be BIP-125-replaceable (RBF).
     ///
     /// # Warning
@@ -821,6 +1005,343 @@ impl Transaction {
         self.input.iter().any(|input| input.sequence.is_rbf())
     }
 
+    /// Sorts the inputs and outputs of this transaction as specified by [BIP69].
+    ///
+    /// Inputs are sorted by previous txid, compared in the displayed (reversed) byte order, then by
+    /// output index. Outputs are sorted by value then by `script_pubkey` bytes. Sorting invalidates
+    /// any signatures already present.
+    ///
+    /// [BIP69]: https://github.com/bitcoin/bips/blob/master/bip-0069.mediawiki
+    pub fn sort_bip69(&mut self) {
+        self.input.sort_by(bip69_cmp_inputs);
+        self.output.sort_by(bip69_cmp_outputs);
+    }
+
+    /// Returns true if the inputs and outputs of this transaction are sorted as specified by BIP69.
+    ///
+    /// See [`Transaction::sort_bip69`].
+    pub fn is_bip69_sorted(&self) -> bool {
+        self.input.windows(2).all(|w| bip69_cmp_inputs(&w[0], &w[1]) != cmp::Ordering::Greater)
+            && self
+                .output
+                .windows(2)
+                .all(|w| bip69_cmp_outputs(&w[0], &w[1]) != cmp::Ordering::Greater)
+    }
+
+    /// Checks that no two inputs of this transaction spend the same outpoint (consensus rule).
+    pub fn check_duplicate_inputs(&self) -> Result<(), DuplicateInputError> {
+        let mut seen = BTreeSet::new();
+        for input in &self.input {
+            if !seen.insert(input.previous_output) {
+                return Err(DuplicateInputError { duplicated_outpoint: input.previous_output });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that this is not a coinbase transaction, which must never be accepted for relay.
+    ///
+    /// This should be the first check when accepting a transaction into the mempool.
+    pub fn verify_not_coinbase(&self) -> Result<(), UnexpectedCoinbaseError> {
+        if self.is_coinbase() {
+            return Err(UnexpectedCoinbaseError::IsCoinbase);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if this is not a coinbase transaction but one of its inputs spends the null
+    /// outpoint, which makes the transaction invalid.
+    pub fn has_null_outpoint_non_coinbase(&self) -> bool {
+        !self.is_coinbase() && self.input.iter().any(|input| input.previous_output.is_null())
+    }
+
+    /// Checks this transaction against the relay policy of the reference node.
+    ///
+    /// This covers the context-free standardness rules: the version must be 1 or 2, every
+    /// scriptSig must be at most [`MAX_STANDARD_SCRIPTSIG_SIZE`] bytes, every output must be
+    /// P2PKH, P2SH, P2WPKH, P2WSH or a single `OP_RETURN` of at most [`MAX_OP_RETURN_RELAY`]
+    /// bytes, and the weight must not exceed [`Transaction::MAX_STANDARD_WEIGHT`].
+    ///
+    /// The rules are currently the same for every network.
+    ///
+    /// [`MAX_STANDARD_SCRIPTSIG_SIZE`]: crate::policy::MAX_STANDARD_SCRIPTSIG_SIZE
+    /// [`MAX_OP_RETURN_RELAY`]: crate::policy::MAX_OP_RETURN_RELAY
+    pub fn is_standard(&self, _network: Network) -> Result<(), NonStandardError> {
+        if !self.version.is_standard() {
+            return Err(NonStandardError::Version(self.version));
+        }
+
+        for (index, input) in self.input.iter().enumerate() {
+            let size = input.script_sig.len();
+            if size > MAX_STANDARD_SCRIPTSIG_SIZE as usize {
+                return Err(NonStandardError::ScriptSigSize { index, size });
+            }
+        }
+
+        let mut op_returns = 0;
+        for (index, output) in self.output.iter().enumerate() {
+            let script = &output.script_pubkey;
+            if script.is_op_return() {
+                if script.len() > MAX_OP_RETURN_RELAY as usize {
+                    return Err(NonStandardError::OpReturnSize { index, size: script.len() });
+                }
+                op_returns += 1;
+            } else if !(script.is_p2pkh()
+                || script.is_p2sh()
+                || script.is_p2wpkh()
+                || script.is_p2wsh())
+            {
+                return Err(NonStandardError::ScriptPubKey { index });
+            }
+        }
+        if op_returns > 1 {
+            return Err(NonStandardError::MultipleOpReturn(op_returns));
+        }
+
+        let weight = self.weight();
+        if weight > Self::MAX_STANDARD_WEIGHT {
+            return Err(NonStandardError::Weight(weight));
+        }
+
+        Ok(())
+    }
+
+    /// Estimates the fee of a transaction spending `inputs` to `num_outputs` recipients plus one
+    /// change output, without constructing it.
+    ///
+    /// Input weights are predicted from the type of each spent output (see [`input_weight`]) and
+    /// all outputs, the recipients' included, are assumed to be of `change_type`. Returns `None`
+    /// if an input is of a type not covered by [`ScriptType`] or the fee overflows.
+    pub fn estimate_fee_for_send(
+        inputs: &[TxOut],
+        num_outputs: usize,
+        change_type: ScriptType,
+        fee_rate: FeeRate,
+    ) -> Option<Amount> {
+        let mut weight = transaction_overhead_weight();
+        for input in inputs {
+            weight += input_weight(ScriptType::from_script_pubkey(&input.script_pubkey)?);
+        }
+        weight += output_weight(change_type) * (num_outputs as u64 + 1);
+        fee_rate.fee_wu(weight)
+    }
+
+    /// Returns the predicted weight of this transaction with one more input spending an output
+    /// of type `input_type`.
+    ///
+    /// The new input is predicted as in [`input_weight`]. This accounts for the input count
+    /// `VarInt` growing and, when the first segwit input is added, for the segwit marker and flag
+    /// and the empty witnesses of the existing inputs.
+    pub fn weight_with_additional_input(&self, input_type: ScriptType) -> Weight {
+        let prediction = input_type.input_prediction();
+        let input_count = self.input.len();
+
+        // Lengths of txid, index and sequence: (32, 4, 4).
+        let base_size = self.base_size()
+            + prediction.script_size
+            + 32
+            + 4
+            + 4
+            + VarInt::from(input_count + 1).size()
+            - VarInt::from(input_count).size();
+
+        let segwit =
+            prediction.witness_size > 0 || self.input.iter().any(|input| !input.witness.is_empty());
+        let witness_size = if segwit {
+            let existing = self.input.iter().map(|input| input.witness.size()).sum::<usize>();
+            // Marker and flag, and an empty witness is a single zero byte.
+            2 + existing + cmp::max(prediction.witness_size, 1)
+        } else {
+            0
+        };
+
+        Weight::from_wu_usize(base_size * 4 + witness_size)
+    }
+
+    /// Returns the predicted weight of this transaction with one more output paying to
+    /// `output_type`.
+    ///
+    /// This accounts for the output count `VarInt` growing, see [`output_weight`].
+    pub fn weight_with_additional_output(&self, output_type: ScriptType) -> Weight {
+        let output_count = self.output.len();
+        let count_growth =
+            VarInt::from(output_count + 1).size() - VarInt::from(output_count).size();
+        self.weight() + output_weight(output_type) + Weight::from_wu_usize(count_growth * 4)
+    }
+
+    /// Creates a BIP-125 replacement of this transaction paying `new_fee_rate`.
+    ///
+    /// The fee increase is taken from the output at `change_output_index` and every input that
+    /// does not already signal RBF gets [`Sequence::ENABLE_RBF_NO_LOCKTIME`]. `input_values` are
+    /// the values of the outputs spent by each input, in order.
+    ///
+    /// The new fee must exceed the original fee by at least [`FeeRate::BROADCAST_MIN`] for the
+    /// size of the transaction, as required for the replacement to be relayed.
+    ///
+    /// The returned transaction keeps the original scriptSigs and witnesses so its weight matches,
+    /// these are not valid for the new transaction and all inputs must be signed again.
+    pub fn rbf_replacement(
+        &self,
+        new_fee_rate: FeeRate,
+        change_output_index: usize,
+        input_values: &[Amount],
+    ) -> Result<Transaction, RbfError> {
+        if input_values.len() != self.input.len() {
+            return Err(RbfError::InputValuesMismatch {
+                inputs: self.input.len(),
+                values: input_values.len(),
+            });
+        }
+        let change = self
+            .output
+            .get(change_output_index)
+            .ok_or(RbfError::InvalidChangeIndex(change_output_index))?;
+
+        let input_total = input_values
+            .iter()
+            .try_fold(Amount::ZERO, |total, value| total.checked_add(*value))
+            .ok_or(RbfError::Overflow)?;
+        let output_total = self
+            .output
+            .iter()
+            .try_fold(Amount::ZERO, |total, output| total.checked_add(output.value))
+            .ok_or(RbfError::Overflow)?;
+        let old_fee = input_total.checked_sub(output_total).ok_or(RbfError::NegativeFee)?;
+
+        let weight = self.weight();
+        let new_fee = new_fee_rate.fee_wu(weight).ok_or(RbfError::Overflow)?;
+        let min_fee = FeeRate::BROADCAST_MIN
+            .fee_wu(weight)
+            .and_then(|incremental| old_fee.checked_add(incremental))
+            .ok_or(RbfError::Overflow)?;
+        if new_fee < min_fee {
+            return Err(RbfError::FeeTooLow { fee: new_fee, required: min_fee });
+        }
+
+        let bump = new_fee - old_fee;
+        let new_change = change
+            .value
+            .checked_sub(bump)
+            .filter(|value| *value >= change.script_pubkey.dust_value())
+            .ok_or(RbfError::InsufficientChange { available: change.value, required: bump })?;
+
+        let mut replacement = self.clone();
+        replacement.output[change_output_index].value = new_change;
+        for input in replacement.input.iter_mut() {
+            if !input.sequence.is_rbf() {
+                input.sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+            }
+        }
+        Ok(replacement)
+    }
+
+    /// Computes the legacy (pre-segwit) signature hash of the input at `input_index`.
+    ///
+    /// `script_code` is the script being satisfied, usually the script pubkey of the spent output.
+    /// For `SIGHASH_SINGLE` inputs without an output at the same index the hash is `1`, as in Core
+    /// (the "`SIGHASH_SINGLE` bug"). See [`SighashCache::legacy_signature_hash`].
+    ///
+    /// # Errors
+    ///
+    /// If `input_index` is out of bounds.
+    pub fn legacy_sighash(
+        &self,
+        input_index: usize,
+        script_code: &Script,
+        sighash_type: EcdsaSighashType,
+    ) -> Result<sighash::LegacySighash, sighash::Error> {
+        SighashCache::new(self).legacy_signature_hash(
+            input_index,
+            script_code,
+            sighash_type.to_u32(),
+        )
+    }
+
+    /// Signs the input at `input_index` that spends `spent_output` with `key`.
+    ///
+    /// The sighash algorithm is picked from the type of `spent_output`, P2PKH, P2WPKH and
+    /// P2SH-P2WPKH outputs are supported. The returned signature still has to be placed in the
+    /// input's `script_sig` or `witness`.
+    #[doc(alias = "compute_input_signature")]
+    pub fn sign_input<C: Signing>(
+        &self,
+        input_index: usize,
+        key: &PrivateKey,
+        spent_output: &TxOut,
+        sighash_type: EcdsaSighashType,
+        secp: &Secp256k1<C>,
+    ) -> Result<ecdsa::Signature, SignInputError> {
+        let pubkey = key.public_key(secp);
+        let spk = &spent_output.script_pubkey;
+        let mut cache = SighashCache::new(self);
+
+        let sighash = if spk.is_p2pkh() {
+            if *spk != ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()) {
+                return Err(SignInputError::KeyMismatch);
+            }
+            cache.legacy_signature_hash(input_index, spk, sighash_type.to_u32())?.to_byte_array()
+        } else if spk.is_p2wpkh() || spk.is_p2sh() {
+            let wpubkey_hash = pubkey.wpubkey_hash().ok_or(SignInputError::UncompressedKey)?;
+            let script_code = ScriptBuf::new_p2wpkh(&wpubkey_hash);
+            let matches =
+                if spk.is_p2sh() { *spk == script_code.to_p2sh() } else { *spk == script_code };
+            if !matches {
+                return Err(SignInputError::KeyMismatch);
+            }
+            cache
+                .p2wpkh_signature_hash(input_index, &script_code, spent_output.value, sighash_type)?
+                .to_byte_array()
+        } else {
+            return Err(SignInputError::UnsupportedScript);
+        };
+
+        let msg = Message::from_digest(sighash);
+        Ok(ecdsa::Signature { sig: secp.sign_ecdsa(&msg, &key.inner), hash_ty: sighash_type })
+    }
+
+    /// Sets the scriptSig of each listed input to spend a P2PKH output with its signature and key.
+    ///
+    /// Each entry in `sigs` is the input index, the signature (e.g. from [`Self::sign_input`]) and
+    /// the public key of the signing key.
+    pub fn with_p2pkh_inputs_signed(
+        mut self,
+        sigs: &[(usize, ecdsa::Signature, PublicKey)],
+    ) -> Result<Transaction, SignInputError> {
+        let inputs = self.input.len();
+        for (index, sig, pubkey) in sigs {
+            let input = self
+                .input
+                .get_mut(*index)
+                .ok_or(SignInputError::IndexOutOfBounds { index: *index, inputs })?;
+            input.script_sig =
+                Builder::new().push_slice(sig.serialize()).push_key(pubkey).into_script();
+        }
+        Ok(self)
+    }
+
+    /// Sets the witness of each listed input to spend a P2WPKH output with its signature and key.
+    ///
+    /// Each entry in `sigs` is the input index, the signature (e.g. from [`Self::sign_input`]) and
+    /// the public key of the signing key, which must be compressed. The scriptSig is left as is so
+    /// P2SH-P2WPKH inputs must have their redeem script pushed separately.
+    pub fn with_p2wpkh_inputs_signed(
+        mut self,
+        sigs: &[(usize, ecdsa::Signature, PublicKey)],
+    ) -> Result<Transaction, SignInputError> {
+        let inputs = self.input.len();
+        for (index, sig, pubkey) in sigs {
+            if !pubkey.compressed {
+                return Err(SignInputError::UncompressedKey);
+            }
+            let input = self
+                .input
+                .get_mut(*index)
+                .ok_or(SignInputError::IndexOutOfBounds { index: *index, inputs })?;
+            input.witness = Witness::p2wpkh(sig, &pubkey.inner);
+        }
+        Ok(self)
+    }
+
     /// Returns true if this [`Transaction`]'s absolute timelock is satisfied at `height`/`time`.
     ///
     /// # Returns
@@ -828,6 +1349,7 @@ impl Transaction {
     /// By definition if the lock time is not enabled the transaction's absolute timelock is
     /// considered to be satisfied i.e., there are no timelock constraints restricting this
     /// transaction from being mined immediately.
+    #[doc(alias = "absolute_time_lock_satisfied")]
     pub fn is_absolute_timelock_satisfied(&self, height: Height, time: Time) -> bool {
         if !self.is_lock_time_enabled() {
             return true;
@@ -838,8 +1360,34 @@ impl Transaction {
     /// Returns `true` if this transactions nLockTime is enabled ([BIP-65]).
     ///
     /// [BIP-65]: https://github.com/bitcoin/bips/blob/master/bip-0065.mediawiki
+    #[doc(alias = "is_nlock_time_enforced")]
     pub fn is_lock_time_enabled(&self) -> bool { self.input.iter().any(|i| i.enables_lock_time()) }
 
+    /// Returns the lowest height at which this transaction's height-based absolute timelock is
+    /// satisfied, see [`Transaction::is_absolute_timelock_satisfied`].
+    ///
+    /// The transaction can be mined in the block following this height. Returns `None` if the
+    /// lock time is time-based or not enabled.
+    pub fn locktime_satisfaction_height(&self) -> Option<u32> {
+        match self.lock_time {
+            absolute::LockTime::Blocks(height) if self.is_lock_time_enabled() =>
+                Some(height.to_consensus_u32()),
+            _ => None,
+        }
+    }
+
+    /// Returns the lowest median time past at which this transaction's time-based absolute
+    /// timelock is satisfied, see [`Transaction::is_absolute_timelock_satisfied`].
+    ///
+    /// Returns `None` if the lock time is height-based or not enabled.
+    pub fn locktime_satisfaction_time(&self) -> Option<u32> {
+        match self.lock_time {
+            absolute::LockTime::Seconds(time) if self.is_lock_time_enabled() =>
+                Some(time.to_consensus_u32()),
+            _ => None,
+        }
+    }
+
     /// Returns an iterator over lengths of `script_pubkey`s in the outputs.
     ///
     /// This is useful in combination with [`predict_weight`] if you have the transaction already
@@ -865,10 +1413,11 @@ impl Transaction {
     where
         S: FnMut(&OutPoint) -> Option<TxOut>,
     {
-        let mut cost = self.count_p2pk_p2pkh_sigops().saturating_mul(4);
+        let mut cost = self.count_p2pk_p2pkh_sigops().saturating_mul(LEGACY_SIGOP_SCALE);
 
         // coinbase tx is correctly handled because `spent` will always returns None.
-        cost = cost.saturating_add(self.count_p2sh_sigops(&mut spent).saturating_mul(4));
+        cost = cost
+            .saturating_add(self.count_p2sh_sigops(&mut spent).saturating_mul(LEGACY_SIGOP_SCALE));
         cost.saturating_add(self.count_witness_sigops(&mut spent))
     }
 
@@ -919,21 +1468,6 @@ impl Transaction {
     where
         S: FnMut(&OutPoint) -> Option<TxOut>,
     {
-        fn count_sigops_with_witness_program(witness: &Witness, witness_program: &Script) -> usize {
-            if witness_program.is_p2wpkh() {
-                1
-            } else if witness_program.is_p2wsh() {
-                // Treat the last item of the witness as the witnessScript
-                return witness
-                    .last()
-                    .map(Script::from_bytes)
-                    .map(|s| s.count_sigops())
-                    .unwrap_or(0);
-            } else {
-                0
-            }
-        }
-
         fn count_sigops(prevout: TxOut, input: &TxIn) -> usize {
             let script_sig = &input.script_sig;
             let witness = &input.witness;
@@ -952,8 +1486,9 @@ impl Transaction {
                 return 0;
             };
 
-            // This will return 0 if the redeemScript wasn't a witness program
-            count_sigops_with_witness_program(witness, witness_program)
+            // This will return 0 if the redeemScript wasn't a witness program.
+            // Treat the last item of the witness as the witnessScript.
+            witness_program.count_sigops_witness(witness.last().map(Script::from_bytes))
         }
 
         let mut count: usize = 0;
@@ -978,105 +1513,541 @@ impl Transaction {
     }
 }
 
-/// The transaction version.
-///
-/// Currently, as specified by [BIP-68], only version 1 and 2 are considered standard.
-///
-/// Standardness of the inner `i32` is not an invariant because you are free to create transactions
-/// of any version, transactions with non-standard version numbers will not be relayed by the
-/// Bitcoin network.
-///
-/// [BIP-68]: https://github.com/bitcoin/bips/blob/master/bip-0068.mediawiki
-#[derive(Copy, PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "serde", serde(crate = "actual_serde"))]
-pub struct Version(pub i32);
+/// Compares inputs by previous txid in displayed byte order, then by output index (BIP69).
+fn bip69_cmp_inputs(a: &TxIn, b: &TxIn) -> cmp::Ordering {
+    let txid = |input: &TxIn| {
+        let mut bytes = input.previous_output.txid.to_byte_array();
+        bytes.reverse();
+        bytes
+    };
+    txid(a).cmp(&txid(b)).then(a.previous_output.vout.cmp(&b.previous_output.vout))
+}
 
-impl Version {
-    /// The original Bitcoin transaction version (pre-BIP-68).
-    pub const ONE: Self = Self(1);
+/// Compares outputs by value, then by `script_pubkey` bytes (BIP69).
+fn bip69_cmp_outputs(a: &TxOut, b: &TxOut) -> cmp::Ordering {
+    a.value.cmp(&b.value).then_with(|| a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
+}
 
-    /// The second Bitcoin transaction version (post-BIP-68).
-    pub const TWO: Self = Self(2);
+/// A description of a transaction in terms of the outputs it spends, used to build it.
+///
+/// Mostly useful in tests that need transactions with specific properties, since the spent outputs
+/// are known the transaction can be signed with [`TransactionTemplate::build_signed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionTemplate {
+    /// The inputs, each as the outpoint spent, the script pubkey and value of the spent output and
+    /// the input's witness if it is already known.
+    pub inputs: Vec<(OutPoint, ScriptBuf, Amount, Option<Witness>)>,
+    /// The outputs, each as its script pubkey and value.
+    pub outputs: Vec<(ScriptBuf, Amount)>,
+    /// The transaction version.
+    pub version: Version,
+    /// The transaction lock time.
+    pub lock_time: absolute::LockTime,
+}
 
-    /// Creates a non-standard transaction version.
-    pub fn non_standard(version: i32) -> Version { Self(version) }
+impl TransactionTemplate {
+    /// Builds the transaction with empty scriptSigs and the given witnesses, if any.
+    pub fn build_unsigned(self) -> Transaction {
+        Transaction {
+            version: self.version,
+            lock_time: self.lock_time,
+            input: self
+                .inputs
+                .into_iter()
+                .map(|(previous_output, _, _, witness)| TxIn {
+                    previous_output,
+                    witness: witness.unwrap_or_default(),
+                    ..Default::default()
+                })
+                .collect(),
+            output: self
+                .outputs
+                .into_iter()
+                .map(|(script_pubkey, value)| TxOut { value, script_pubkey })
+                .collect(),
+        }
+    }
 
-    /// Returns true if this transaction version number is considered standard.
-    pub fn is_standard(&self) -> bool { *self == Version::ONE || *self == Version::TWO }
-}
+    /// Builds the transaction and signs its inputs with `SIGHASH_ALL`.
+    ///
+    /// Each input without a witness is signed with the first of `keys` its spent output pays to,
+    /// P2PKH, P2WPKH and P2SH-P2WPKH outputs are supported (see [`Transaction::sign_input`]).
+    /// Inputs with a witness are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// [`SignInputError::KeyMismatch`] if none of `keys` can sign an input, or any other error
+    /// signing an input.
+    pub fn build_signed<C: Signing>(
+        self,
+        keys: &[PrivateKey],
+        secp: &Secp256k1<C>,
+    ) -> Result<Transaction, SignInputError> {
+        let spent = self
+            .inputs
+            .iter()
+            .map(|(_, script_pubkey, value, witness)| {
+                (TxOut { value: *value, script_pubkey: script_pubkey.clone() }, witness.is_some())
+            })
+            .collect::<Vec<_>>();
+        let unsigned = self.build_unsigned();
 
-impl Encodable for Version {
-    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        self.0.consensus_encode(w)
+        let mut signed = unsigned.clone();
+        for (index, (spent_output, has_witness)) in spent.iter().enumerate() {
+            if *has_witness {
+                continue;
+            }
+            let (sig, pubkey) = sign_with_any(&unsigned, index, keys, spent_output, secp)?;
+            let spk = &spent_output.script_pubkey;
+            let input = &mut signed.input[index];
+            if spk.is_p2pkh() {
+                input.script_sig =
+                    Builder::new().push_slice(sig.serialize()).push_key(&pubkey).into_script();
+            } else {
+                input.witness = Witness::p2wpkh(&sig, &pubkey.inner);
+                if spk.is_p2sh() {
+                    // Signing succeeded so the key is compressed.
+                    let wpubkey_hash = pubkey.wpubkey_hash().expect("compressed key");
+                    let redeem_script = ScriptBuf::new_p2wpkh(&wpubkey_hash);
+                    let redeem_script = <&script::PushBytes>::try_from(redeem_script.as_bytes())
+                        .expect("22 bytes is a valid push");
+                    input.script_sig = Builder::new().push_slice(redeem_script).into_script();
+                }
+            }
+        }
+        Ok(signed)
     }
 }
 
-impl Decodable for Version {
-    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
-        Decodable::consensus_decode(r).map(Version)
+/// Signs input `index` of `tx` with the first of `keys` that `spent_output` pays to.
+fn sign_with_any<C: Signing>(
+    tx: &Transaction,
+    index: usize,
+    keys: &[PrivateKey],
+    spent_output: &TxOut,
+    secp: &Secp256k1<C>,
+) -> Result<(ecdsa::Signature, PublicKey), SignInputError> {
+    for key in keys {
+        match tx.sign_input(index, key, spent_output, EcdsaSighashType::All, secp) {
+            Ok(sig) => return Ok((sig, key.public_key(secp))),
+            Err(SignInputError::KeyMismatch) | Err(SignInputError::UncompressedKey) => continue,
+            Err(e) => return Err(e),
+        }
     }
+    Err(SignInputError::KeyMismatch)
 }
 
-impl_consensus_encoding!(TxOut, value, script_pubkey);
+/// The serialized size of each part of a transaction, see [`Transaction::size_breakdown`].
+///
+/// All sizes are in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxSizeBreakdown {
+    /// Size of the version number.
+    pub version: usize,
+    /// Sizes of the inputs, in order.
+    pub inputs: Vec<InputSize>,
+    /// Sizes of the outputs, in order.
+    pub outputs: Vec<OutputSize>,
+    /// Size of the lock time.
+    pub locktime: usize,
+    /// Size of everything else: the segwit marker and flag, the input and output counts and the
+    /// outpoint and sequence of every input.
+    pub overhead: usize,
+}
 
-impl Encodable for OutPoint {
-    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        let len = self.txid.consensus_encode(w)?;
-        Ok(len + self.vout.consensus_encode(w)?)
+impl TxSizeBreakdown {
+    /// Returns the sum of all sizes, i.e. the serialized size of the transaction.
+    pub fn total(&self) -> usize {
+        self.version
+            + self.inputs.iter().map(|input| input.script_sig + input.witness).sum::<usize>()
+            + self.outputs.iter().map(|output| output.value + output.script_pubkey).sum::<usize>()
+            + self.locktime
+            + self.overhead
     }
 }
-impl Decodable for OutPoint {
-    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
-        Ok(OutPoint {
-            txid: Decodable::consensus_decode(r)?,
-            vout: Decodable::consensus_decode(r)?,
-        })
-    }
+
+/// The serialized size of a transaction input, see [`TxSizeBreakdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputSize {
+    /// Size of the scriptSig including its length prefix.
+    pub script_sig: usize,
+    /// Size of the witness including its item count, zero if the transaction has no witness data.
+    pub witness: usize,
 }
 
-impl Encodable for TxIn {
-    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        let mut len = 0;
-        len += self.previous_output.consensus_encode(w)?;
-        len += self.script_sig.consensus_encode(w)?;
-        len += self.sequence.consensus_encode(w)?;
-        Ok(len)
-    }
+/// The serialized size of a transaction output, see [`TxSizeBreakdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputSize {
+    /// Size of the value.
+    pub value: usize,
+    /// Size of the scriptPubkey including its length prefix.
+    pub script_pubkey: usize,
 }
-impl Decodable for TxIn {
-    #[inline]
-    fn consensus_decode_from_finite_reader<R: io::Read + ?Sized>(
-        r: &mut R,
-    ) -> Result<Self, encode::Error> {
-        Ok(TxIn {
-            previous_output: Decodable::consensus_decode_from_finite_reader(r)?,
-            script_sig: Decodable::consensus_decode_from_finite_reader(r)?,
-            sequence: Decodable::consensus_decode_from_finite_reader(r)?,
-            witness: Witness::default(),
-        })
-    }
+
+/// Two inputs spend the same outpoint, see [`Transaction::check_duplicate_inputs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DuplicateInputError {
+    /// The outpoint spent more than once.
+    pub duplicated_outpoint: OutPoint,
 }
 
-impl Encodable for Sequence {
-    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        self.0.consensus_encode(w)
+impl fmt::Display for DuplicateInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "outpoint {} is spent by more than one input", self.duplicated_outpoint)
     }
 }
 
-impl Decodable for Sequence {
-    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
-        Decodable::consensus_decode(r).map(Sequence)
-    }
+#[cfg(feature = "std")]
+impl std::error::Error for DuplicateInputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
 }
 
-impl Encodable for Transaction {
-    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        let mut len = 0;
-        len += self.version.consensus_encode(w)?;
+/// A coinbase transaction was found where none is allowed, see [`Transaction::verify_not_coinbase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnexpectedCoinbaseError {
+    /// The transaction is a coinbase transaction.
+    IsCoinbase,
+}
 
-        // Legacy transaction serialization format only includes inputs and outputs.
-        if !self.use_segwit_serialization() {
+impl fmt::Display for UnexpectedCoinbaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use UnexpectedCoinbaseError::*;
+
+        match *self {
+            IsCoinbase => write!(f, "coinbase transaction is not allowed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnexpectedCoinbaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use UnexpectedCoinbaseError::*;
+
+        match *self {
+            IsCoinbase => None,
+        }
+    }
+}
+
+/// An error creating an `OP_RETURN` output, see [`TxOut::new_op_return`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OpReturnError {
+    /// The data is longer than the maximum relayed by default.
+    DataTooLong {
+        /// The length of the data.
+        len: usize,
+        /// The maximum length of the data.
+        max: usize,
+    },
+}
+
+impl fmt::Display for OpReturnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use OpReturnError::*;
+
+        match *self {
+            DataTooLong { len, max } =>
+                write!(f, "OP_RETURN data of {} bytes exceeds the maximum of {} bytes", len, max),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpReturnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use OpReturnError::*;
+
+        match *self {
+            DataTooLong { .. } => None,
+        }
+    }
+}
+
+/// A transaction failed the relay policy checks of [`Transaction::is_standard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NonStandardError {
+    /// The transaction version is neither 1 nor 2.
+    Version(Version),
+    /// The scriptSig of the input at `index` is too large.
+    ScriptSigSize {
+        /// Index of the offending input.
+        index: usize,
+        /// Size of its scriptSig in bytes.
+        size: usize,
+    },
+    /// The scriptPubKey of the output at `index` is not a standard template.
+    ScriptPubKey {
+        /// Index of the offending output.
+        index: usize,
+    },
+    /// The `OP_RETURN` output at `index` carries too much data.
+    OpReturnSize {
+        /// Index of the offending output.
+        index: usize,
+        /// Size of its scriptPubKey in bytes.
+        size: usize,
+    },
+    /// The transaction has more than one `OP_RETURN` output (contains the count).
+    MultipleOpReturn(usize),
+    /// The transaction weight exceeds [`Transaction::MAX_STANDARD_WEIGHT`].
+    Weight(Weight),
+}
+
+impl fmt::Display for NonStandardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use NonStandardError as E;
+
+        match *self {
+            E::Version(ref v) => write!(f, "non-standard transaction version {}", v.0),
+            E::ScriptSigSize { index, size } => write!(
+                f,
+                "scriptSig of input {} is {} bytes (max {})",
+                index, size, MAX_STANDARD_SCRIPTSIG_SIZE
+            ),
+            E::ScriptPubKey { index } =>
+                write!(f, "scriptPubKey of output {} is non-standard", index),
+            E::OpReturnSize { index, size } => write!(
+                f,
+                "OP_RETURN output {} is {} bytes (max {})",
+                index, size, MAX_OP_RETURN_RELAY
+            ),
+            E::MultipleOpReturn(n) => write!(f, "{} OP_RETURN outputs (max 1)", n),
+            E::Weight(ref w) => write!(f, "transaction weight {} exceeds the standard maximum", w),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonStandardError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+}
+
+/// An error creating a replacement transaction with [`Transaction::rbf_replacement`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RbfError {
+    /// The number of input values does not match the number of inputs.
+    InputValuesMismatch {
+        /// Number of inputs in the transaction.
+        inputs: usize,
+        /// Number of input values provided.
+        values: usize,
+    },
+    /// There is no output at the given change index.
+    InvalidChangeIndex(usize),
+    /// The outputs of the original transaction are worth more than its inputs.
+    NegativeFee,
+    /// The requested fee rate does not pay enough over the original fee.
+    FeeTooLow {
+        /// Fee paid at the requested fee rate.
+        fee: Amount,
+        /// Minimum fee for the replacement to be relayed.
+        required: Amount,
+    },
+    /// The change output cannot cover the fee increase without becoming dust.
+    InsufficientChange {
+        /// Value of the change output.
+        available: Amount,
+        /// Fee increase to be taken from the change output.
+        required: Amount,
+    },
+    /// An amount overflowed.
+    Overflow,
+}
+
+impl fmt::Display for RbfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RbfError::*;
+
+        match *self {
+            InputValuesMismatch { inputs, values } =>
+                write!(f, "{} input values provided for {} inputs", values, inputs),
+            InvalidChangeIndex(index) => write!(f, "no change output at index {}", index),
+            NegativeFee => write!(f, "transaction outputs exceed its inputs"),
+            FeeTooLow { fee, required } =>
+                write!(f, "replacement fee {} is below the required {}", fee, required),
+            InsufficientChange { available, required } => write!(
+                f,
+                "change output of {} cannot cover a fee increase of {}",
+                available, required
+            ),
+            Overflow => write!(f, "amount overflow"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RbfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use RbfError::*;
+
+        match *self {
+            InputValuesMismatch { .. }
+            | InvalidChangeIndex(_)
+            | NegativeFee
+            | FeeTooLow { .. }
+            | InsufficientChange { .. }
+            | Overflow => None,
+        }
+    }
+}
+
+/// An error signing an input with [`Transaction::sign_input`] or applying signatures to inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignInputError {
+    /// The spent output is not a P2PKH, P2WPKH or P2SH-P2WPKH output.
+    UnsupportedScript,
+    /// The spent output does not pay to the signing key.
+    KeyMismatch,
+    /// Segwit outputs can only be signed with compressed keys.
+    UncompressedKey,
+    /// Computing the sighash failed.
+    Sighash(sighash::Error),
+    /// There is no input at the given index.
+    IndexOutOfBounds {
+        /// Requested input index.
+        index: usize,
+        /// Number of inputs in the transaction.
+        inputs: usize,
+    },
+}
+
+impl fmt::Display for SignInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SignInputError::*;
+
+        match *self {
+            UnsupportedScript => write!(f, "unsupported spent output script"),
+            KeyMismatch => write!(f, "spent output does not pay to the signing key"),
+            UncompressedKey => write!(f, "segwit outputs require a compressed key"),
+            Sighash(ref e) => write_err!(f, "sighash computation"; e),
+            IndexOutOfBounds { index, inputs } =>
+                write!(f, "no input at index {} in transaction with {} inputs", index, inputs),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SignInputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use SignInputError::*;
+
+        match *self {
+            Sighash(ref e) => Some(e),
+            UnsupportedScript | KeyMismatch | UncompressedKey | IndexOutOfBounds { .. } => None,
+        }
+    }
+}
+
+impl From<sighash::Error> for SignInputError {
+    fn from(e: sighash::Error) -> Self { Self::Sighash(e) }
+}
+
+/// The transaction version.
+///
+/// Currently, as specified by [BIP-68], only version 1 and 2 are considered standard.
+///
+/// Standardness of the inner `i32` is not an invariant because you are free to create transactions
+/// of any version, transactions with non-standard version numbers will not be relayed by the
+/// Bitcoin network.
+///
+/// [BIP-68]: https://github.com/bitcoin/bips/blob/master/bip-0068.mediawiki
+#[derive(Copy, PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(crate = "actual_serde"))]
+pub struct Version(pub i32);
+
+impl Version {
+    /// The original Bitcoin transaction version (pre-BIP-68).
+    pub const ONE: Self = Self(1);
+
+    /// The second Bitcoin transaction version (post-BIP-68).
+    pub const TWO: Self = Self(2);
+
+    /// Creates a non-standard transaction version.
+    pub fn non_standard(version: i32) -> Version { Self(version) }
+
+    /// Returns true if this transaction version number is considered standard.
+    pub fn is_standard(&self) -> bool { *self == Version::ONE || *self == Version::TWO }
+}
+
+impl Encodable for Version {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        self.0.consensus_encode(w)
+    }
+}
+
+impl Decodable for Version {
+    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
+        Decodable::consensus_decode(r).map(Version)
+    }
+}
+
+impl_consensus_encoding!(TxOut, value, script_pubkey);
+
+impl Encodable for OutPoint {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        let len = self.txid.consensus_encode(w)?;
+        Ok(len + self.vout.consensus_encode(w)?)
+    }
+}
+impl Decodable for OutPoint {
+    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
+        Ok(OutPoint {
+            txid: Decodable::consensus_decode(r)?,
+            vout: Decodable::consensus_decode(r)?,
+        })
+    }
+}
+
+impl Encodable for TxIn {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+        len += self.previous_output.consensus_encode(w)?;
+        len += self.script_sig.consensus_encode(w)?;
+        len += self.sequence.consensus_encode(w)?;
+        Ok(len)
+    }
+}
+impl Decodable for TxIn {
+    #[inline]
+    fn consensus_decode_from_finite_reader<R: io::Read + ?Sized>(
+        r: &mut R,
+    ) -> Result<Self, encode::Error> {
+        Ok(TxIn {
+            previous_output: Decodable::consensus_decode_from_finite_reader(r)?,
+            script_sig: Decodable::consensus_decode_from_finite_reader(r)?,
+            sequence: Decodable::consensus_decode_from_finite_reader(r)?,
+            witness: Witness::default(),
+        })
+    }
+}
+
+impl Encodable for Sequence {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        self.0.consensus_encode(w)
+    }
+}
+
+impl Decodable for Sequence {
+    fn consensus_decode<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, encode::Error> {
+        Decodable::consensus_decode(r).map(Sequence)
+    }
+}
+
+impl Encodable for Transaction {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+        len += self.version.consensus_encode(w)?;
+
+        // Legacy transaction serialization format only includes inputs and outputs.
+        if !self.use_segwit_serialization() {
             len += self.input.consensus_encode(w)?;
             len += self.output.consensus_encode(w)?;
         } else {
@@ -1413,6 +2384,89 @@ impl InputWeightPrediction {
     }
 }
 
+/// The kind of script an input spends or an output pays to, for use with [`input_weight`] and
+/// [`output_weight`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ScriptType {
+    /// Pay to (compressed) public key hash.
+    P2pkh,
+    /// Pay to witness public key hash wrapped in pay to script hash.
+    P2shP2wpkh,
+    /// Pay to witness public key hash.
+    P2wpkh,
+    /// Pay to taproot, spent using the key path with the default sighash.
+    P2tr,
+}
+
+impl ScriptType {
+    /// Returns the type of `script_pubkey`, or `None` if it is not one of the supported types.
+    ///
+    /// All P2SH outputs are assumed to be P2SH-P2WPKH, the only wrapped type supported.
+    pub fn from_script_pubkey(script_pubkey: &Script) -> Option<ScriptType> {
+        if script_pubkey.is_p2pkh() {
+            Some(ScriptType::P2pkh)
+        } else if script_pubkey.is_p2sh() {
+            Some(ScriptType::P2shP2wpkh)
+        } else if script_pubkey.is_p2wpkh() {
+            Some(ScriptType::P2wpkh)
+        } else if script_pubkey.is_p2tr() {
+            Some(ScriptType::P2tr)
+        } else {
+            None
+        }
+    }
+
+    /// Prediction for the input spending an output of this type with the largest signature.
+    const fn input_prediction(self) -> InputWeightPrediction {
+        match self {
+            // scriptSig: <sig> <pubkey>
+            ScriptType::P2pkh => InputWeightPrediction::from_slice(1 + 73 + 1 + 33, &[]),
+            // scriptSig: <OP_0 <20-byte hash>>
+            ScriptType::P2shP2wpkh => InputWeightPrediction::from_slice(23, &[73, 33]),
+            ScriptType::P2wpkh => InputWeightPrediction::P2WPKH_MAX,
+            ScriptType::P2tr => InputWeightPrediction::P2TR_KEY_DEFAULT_SIGHASH,
+        }
+    }
+
+    /// Length of the `script_pubkey` of an output of this type.
+    const fn script_pubkey_len(self) -> usize {
+        match self {
+            ScriptType::P2pkh => 25,
+            ScriptType::P2shP2wpkh => 23,
+            ScriptType::P2wpkh => 22,
+            ScriptType::P2tr => 34,
+        }
+    }
+}
+
+/// Returns the weight a typical input spending `script_type` adds to a transaction.
+///
+/// This assumes the largest possible DER-encoded signature (see
+/// [`InputWeightPrediction::P2WPKH_MAX`]) and does not include the transaction overhead, see
+/// [`transaction_overhead_weight`].
+pub const fn input_weight(script_type: ScriptType) -> Weight {
+    let prediction = script_type.input_prediction();
+    // Lengths of txid, index and sequence: (32, 4, 4), all non-witness fields.
+    let weight = (prediction.script_size + 32 + 4 + 4) * 4 + prediction.witness_size;
+    Weight::from_wu(weight as u64)
+}
+
+/// Returns the weight an output paying to `script_type` adds to a transaction.
+pub const fn output_weight(script_type: ScriptType) -> Weight {
+    let script_len = script_type.script_pubkey_len();
+    // 8 bytes of value plus the length-prefixed script_pubkey.
+    let size = 8 + VarInt(script_len as u64).size() + script_len;
+    Weight::from_wu(size as u64 * 4)
+}
+
+/// Returns the weight of the parts of a transaction that are not inputs or outputs.
+///
+/// This is the version, the input and output counts (assuming fewer than 253 of each), the lock
+/// time and the segwit marker and flag. Transactions without any witness do not carry the marker
+/// and flag so are 2 weight units lighter.
+pub const fn transaction_overhead_weight() -> Weight { Weight::from_wu((4 + 1 + 1 + 4) * 4 + 2) }
+
 #[cfg(test)]
 mod tests {
     use core::str::FromStr;
@@ -1424,7 +2478,6 @@ mod tests {
     use crate::blockdata::locktime::absolute;
     use crate::blockdata::script::ScriptBuf;
     use crate::consensus::encode::{deserialize, serialize};
-    use crate::sighash::EcdsaSighashType;
 
     const SOME_TX: &str = "0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000";
 
@@ -1543,6 +2596,705 @@ mod tests {
         assert!(!tx.is_coinbase());
     }
 
+    #[test]
+    fn is_standard() {
+        use crate::blockdata::constants;
+        use crate::blockdata::opcodes::all::OP_RETURN;
+        use crate::blockdata::script::{Builder, PushBytesBuf};
+
+        // The genesis coinbase pays to a bare public key.
+        let genesis = constants::genesis_block(Network::Bitcoin);
+        assert_eq!(
+            genesis.txdata[0].is_standard(Network::Bitcoin),
+            Err(NonStandardError::ScriptPubKey { index: 0 })
+        );
+
+        let tx_bytes = hex!("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000");
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert_eq!(tx.is_standard(Network::Bitcoin), Ok(()));
+
+        let mut bad = tx.clone();
+        bad.version = Version::non_standard(3);
+        assert_eq!(
+            bad.is_standard(Network::Bitcoin),
+            Err(NonStandardError::Version(Version::non_standard(3)))
+        );
+
+        let mut bad = tx.clone();
+        bad.input[0].script_sig = ScriptBuf::from_bytes(vec![0x51; 1651]);
+        assert_eq!(
+            bad.is_standard(Network::Bitcoin),
+            Err(NonStandardError::ScriptSigSize { index: 0, size: 1651 })
+        );
+
+        let op_return = Builder::new().push_opcode(OP_RETURN).push_slice([0u8; 73]).into_script();
+        assert_eq!(op_return.len(), 75);
+        let mut tx_op_return = tx.clone();
+        tx_op_return.output.push(TxOut { value: Amount::ZERO, script_pubkey: op_return.clone() });
+        assert_eq!(tx_op_return.is_standard(Network::Bitcoin), Ok(()));
+
+        let mut bad = tx_op_return.clone();
+        bad.output.push(TxOut { value: Amount::ZERO, script_pubkey: op_return });
+        assert_eq!(bad.is_standard(Network::Bitcoin), Err(NonStandardError::MultipleOpReturn(2)));
+
+        let mut big = ScriptBuf::from_bytes(vec![OP_RETURN.to_u8()]);
+        big.push_slice(PushBytesBuf::try_from(vec![0u8; 83]).unwrap());
+        let mut bad = tx.clone();
+        bad.output.push(TxOut { value: Amount::ZERO, script_pubkey: big });
+        assert_eq!(
+            bad.is_standard(Network::Bitcoin),
+            Err(NonStandardError::OpReturnSize { index: 1, size: 86 })
+        );
+    }
+
+    #[test]
+    fn input_output_weight() {
+        assert_eq!(input_weight(ScriptType::P2pkh), Weight::from_wu(596));
+        assert_eq!(input_weight(ScriptType::P2shP2wpkh), Weight::from_wu(365));
+        assert_eq!(input_weight(ScriptType::P2wpkh), Weight::from_wu(273));
+        assert_eq!(input_weight(ScriptType::P2tr), Weight::from_wu(230));
+
+        assert_eq!(output_weight(ScriptType::P2pkh), Weight::from_wu(136));
+        assert_eq!(output_weight(ScriptType::P2shP2wpkh), Weight::from_wu(128));
+        assert_eq!(output_weight(ScriptType::P2wpkh), Weight::from_wu(124));
+        assert_eq!(output_weight(ScriptType::P2tr), Weight::from_wu(172));
+
+        // One P2WPKH input paying to one P2WPKH output.
+        let predicted = predict_weight_from_slices(&[InputWeightPrediction::P2WPKH_MAX], &[22]);
+        let summed = transaction_overhead_weight()
+            + input_weight(ScriptType::P2wpkh)
+            + output_weight(ScriptType::P2wpkh);
+        assert_eq!(summed, predicted);
+
+        // The 1-in 1-out P2PKH transaction from `nonsegwit_transaction`, which has a maximum size
+        // signature but no segwit marker and flag.
+        let tx_bytes = hex!("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000");
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        let summed = transaction_overhead_weight()
+            + input_weight(ScriptType::P2pkh)
+            + output_weight(ScriptType::P2pkh);
+        assert_eq!(summed, tx.weight() + Weight::from_wu(2));
+    }
+
+    #[test]
+    fn base_weight_without_inputs() {
+        use crate::blockdata::constants::genesis_block;
+
+        let genesis = &genesis_block(Network::Bitcoin).txdata[0];
+        // Version, input and output counts and lock time, with one 8 byte value and 67 byte
+        // script_pubkey output.
+        assert_eq!(
+            genesis.base_weight_without_inputs(),
+            Weight::from_wu((4 + 1 + 1 + 8 + 1 + 67 + 4) * 4)
+        );
+        assert_eq!(
+            genesis.base_weight_without_inputs() + genesis.input_weight_sum(),
+            genesis.weight()
+        );
+
+        let segwit: Transaction = deserialize(&hex!("02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c0100000000ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd2710e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed8941055d3bcb8627d085e94553e62f057dcc00000000")).unwrap();
+        assert_eq!(segwit.base_weight_without_inputs().to_wu() % 4, 2);
+        assert_eq!(
+            segwit.base_weight_without_inputs() + segwit.input_weight_sum(),
+            segwit.weight()
+        );
+
+        let empty = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        assert_eq!(empty.base_weight_without_inputs(), empty.weight());
+        assert_eq!(empty.input_weight_sum(), Weight::ZERO);
+    }
+
+    #[test]
+    fn weight_with_additional_input_and_output() {
+        use crate::crypto::key::WPubkeyHash;
+
+        let p2wpkh_input = TxIn {
+            witness: Witness::from_slice(&[vec![0; 73], vec![0; 33]]),
+            ..Default::default()
+        };
+
+        // Segwit transaction, one more P2WPKH input adds exactly `input_weight`.
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![p2wpkh_input.clone()],
+            output: vec![TxOut::NULL],
+        };
+        let predicted = tx.weight_with_additional_input(ScriptType::P2wpkh);
+        assert_eq!(predicted, tx.weight() + Weight::from_wu(273));
+        tx.input.push(p2wpkh_input.clone());
+        assert_eq!(predicted, tx.weight());
+
+        // Non-segwit transaction, the first segwit input adds the marker, flag and an empty
+        // witness for the existing input.
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let predicted = tx.weight_with_additional_input(ScriptType::P2wpkh);
+        assert_eq!(predicted, tx.weight() + Weight::from_wu(273 + 2 + 1));
+        tx.input.push(p2wpkh_input);
+        assert_eq!(predicted, tx.weight());
+
+        // Legacy input to a segwit transaction needs an empty witness.
+        let predicted = tx.weight_with_additional_input(ScriptType::P2pkh);
+        assert_eq!(predicted, tx.weight() + input_weight(ScriptType::P2pkh) + Weight::from_wu(1));
+
+        // Output count `VarInt` grows from one to three bytes.
+        tx.output = vec![TxOut::NULL; 252];
+        let predicted = tx.weight_with_additional_output(ScriptType::P2wpkh);
+        assert_eq!(predicted, tx.weight() + output_weight(ScriptType::P2wpkh) + Weight::from_wu(8));
+        tx.output.push(TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+        });
+        assert_eq!(predicted, tx.weight());
+    }
+
+    #[test]
+    fn estimate_fee_for_send() {
+        use crate::crypto::key::WPubkeyHash;
+
+        let p2wpkh = TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+        };
+        let rate = FeeRate::from_sat_per_vb_unchecked(10);
+
+        // 42 wu overhead, 2 * 273 wu inputs and 2 * 124 wu outputs: 836 wu, i.e. 209 vbytes.
+        let fee = Transaction::estimate_fee_for_send(
+            &[p2wpkh.clone(), p2wpkh.clone()],
+            1,
+            ScriptType::P2wpkh,
+            rate,
+        );
+        assert_eq!(fee, Some(Amount::from_sat(2_090)));
+        let weight = transaction_overhead_weight()
+            + input_weight(ScriptType::P2wpkh) * 2
+            + output_weight(ScriptType::P2wpkh) * 2;
+        assert_eq!(fee, rate.fee_wu(weight));
+
+        let unknown = TxOut { value: Amount::ZERO, script_pubkey: ScriptBuf::new() };
+        assert_eq!(
+            Transaction::estimate_fee_for_send(&[p2wpkh, unknown], 1, ScriptType::P2wpkh, rate),
+            None
+        );
+    }
+
+    #[test]
+    fn rbf_replacement() {
+        let tx_bytes = hex!("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000");
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert!(!tx.is_explicitly_rbf());
+        let input_values = [tx.output[0].value + Amount::from_sat(10_000)];
+        let old_fee = Amount::from_sat(10_000);
+
+        let rate = FeeRate::from_sat_per_vb_unchecked(100);
+        let replacement = tx.rbf_replacement(rate, 0, &input_values).unwrap();
+        let new_fee = input_values[0] - replacement.output[0].value;
+        assert_eq!(new_fee, rate.fee_wu(tx.weight()).unwrap());
+        assert!(new_fee / replacement.weight() > old_fee / tx.weight());
+        assert!(replacement.is_explicitly_rbf());
+        assert_eq!(replacement.weight(), tx.weight());
+
+        // 52 sat/vB is above the original rate but not by the incremental relay fee.
+        assert_eq!(
+            tx.rbf_replacement(FeeRate::from_sat_per_vb_unchecked(52), 0, &input_values),
+            Err(RbfError::FeeTooLow {
+                fee: Amount::from_sat(10_036),
+                required: Amount::from_sat(10_193)
+            })
+        );
+
+        let mut small_change = tx.clone();
+        small_change.output[0].value = Amount::from_sat(10_000);
+        let input_values = [Amount::from_sat(20_000)];
+        let rate = FeeRate::from_sat_per_vb_unchecked(200);
+        assert_eq!(
+            small_change.rbf_replacement(rate, 0, &input_values),
+            Err(RbfError::InsufficientChange {
+                available: Amount::from_sat(10_000),
+                required: Amount::from_sat(28_600)
+            })
+        );
+
+        assert_eq!(
+            tx.rbf_replacement(rate, 1, &input_values),
+            Err(RbfError::InvalidChangeIndex(1))
+        );
+        assert_eq!(
+            tx.rbf_replacement(rate, 0, &[]),
+            Err(RbfError::InputValuesMismatch { inputs: 1, values: 0 })
+        );
+    }
+
+    #[test]
+    fn sign_input() {
+        use secp256k1::SecretKey;
+
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x01; 32]).unwrap();
+        let key = PrivateKey::new(sk, Network::Bitcoin);
+        let pubkey = key.public_key(&secp);
+        let wpubkey_hash = pubkey.wpubkey_hash().unwrap();
+
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let value = Amount::from_sat(100_010_000);
+        let p2pkh = ScriptBuf::new_p2pkh(&pubkey.pubkey_hash());
+        let p2wpkh = ScriptBuf::new_p2wpkh(&wpubkey_hash);
+        let p2sh_p2wpkh = p2wpkh.to_p2sh();
+
+        for script_pubkey in [p2pkh.clone(), p2wpkh.clone(), p2sh_p2wpkh].iter() {
+            let spent = TxOut { value, script_pubkey: script_pubkey.clone() };
+            let sig = tx.sign_input(0, &key, &spent, EcdsaSighashType::All, &secp).unwrap();
+            assert_eq!(sig.hash_ty, EcdsaSighashType::All);
+
+            let mut cache = SighashCache::new(&tx);
+            let sighash = if script_pubkey.is_p2pkh() {
+                cache.legacy_signature_hash(0, script_pubkey, 1).unwrap().to_byte_array()
+            } else {
+                cache
+                    .p2wpkh_signature_hash(0, &p2wpkh, value, EcdsaSighashType::All)
+                    .unwrap()
+                    .to_byte_array()
+            };
+            let msg = Message::from_digest(sighash);
+            secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).unwrap();
+        }
+
+        let other = PrivateKey::new(SecretKey::from_slice(&[0x02; 32]).unwrap(), Network::Bitcoin);
+        let spent = TxOut { value, script_pubkey: p2pkh };
+        assert_eq!(
+            tx.sign_input(0, &other, &spent, EcdsaSighashType::All, &secp),
+            Err(SignInputError::KeyMismatch)
+        );
+        assert_eq!(
+            tx.sign_input(1, &key, &spent, EcdsaSighashType::All, &secp),
+            Err(SignInputError::Sighash(sighash::Error::IndexOutOfInputsBounds {
+                index: 1,
+                inputs_size: 1
+            }))
+        );
+
+        let mut uncompressed = key;
+        uncompressed.compressed = false;
+        let spent = TxOut { value, script_pubkey: p2wpkh };
+        assert_eq!(
+            tx.sign_input(0, &uncompressed, &spent, EcdsaSighashType::All, &secp),
+            Err(SignInputError::UncompressedKey)
+        );
+
+        let spent = TxOut { value, script_pubkey: ScriptBuf::new_op_return(&[0x01; 4]) };
+        assert_eq!(
+            tx.sign_input(0, &key, &spent, EcdsaSighashType::All, &secp),
+            Err(SignInputError::UnsupportedScript)
+        );
+    }
+
+    #[test]
+    fn apply_signatures_to_inputs() {
+        use secp256k1::SecretKey;
+
+        let secp = Secp256k1::new();
+        let key = PrivateKey::new(SecretKey::from_slice(&[0x01; 32]).unwrap(), Network::Bitcoin);
+        let pubkey = key.public_key(&secp);
+        let value = Amount::from_sat(50_000);
+        let p2pkh = TxOut { value, script_pubkey: ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()) };
+        let p2wpkh =
+            TxOut { value, script_pubkey: ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash().unwrap()) };
+
+        let unsigned = TransactionTemplate {
+            inputs: vec![
+                (OutPoint::new(Txid::all_zeros(), 0), p2pkh.script_pubkey.clone(), value, None),
+                (OutPoint::new(Txid::all_zeros(), 1), p2wpkh.script_pubkey.clone(), value, None),
+            ],
+            outputs: vec![(p2pkh.script_pubkey.clone(), Amount::from_sat(90_000))],
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+        }
+        .build_unsigned();
+        let all = EcdsaSighashType::All;
+        let p2pkh_sig = unsigned.sign_input(0, &key, &p2pkh, all, &secp).unwrap();
+        let p2wpkh_sig = unsigned.sign_input(1, &key, &p2wpkh, all, &secp).unwrap();
+
+        let signed = unsigned
+            .clone()
+            .with_p2pkh_inputs_signed(&[(0, p2pkh_sig, pubkey)])
+            .unwrap()
+            .with_p2wpkh_inputs_signed(&[(1, p2wpkh_sig, pubkey)])
+            .unwrap();
+        assert_eq!(
+            signed.input[0].script_sig,
+            Builder::new().push_slice(p2pkh_sig.serialize()).push_key(&pubkey).into_script()
+        );
+        assert!(signed.input[0].witness.is_empty());
+        assert!(signed.input[1].script_sig.is_empty());
+        assert_eq!(signed.input[1].witness, Witness::p2wpkh(&p2wpkh_sig, &pubkey.inner));
+        assert_eq!(signed.is_standard(Network::Bitcoin), Ok(()));
+
+        assert_eq!(
+            unsigned.clone().with_p2pkh_inputs_signed(&[(2, p2pkh_sig, pubkey)]),
+            Err(SignInputError::IndexOutOfBounds { index: 2, inputs: 2 })
+        );
+        let mut uncompressed = pubkey;
+        uncompressed.compressed = false;
+        assert_eq!(
+            unsigned.with_p2wpkh_inputs_signed(&[(1, p2wpkh_sig, uncompressed)]),
+            Err(SignInputError::UncompressedKey)
+        );
+    }
+
+    #[test]
+    fn transaction_template() {
+        use secp256k1::SecretKey;
+
+        let secp = Secp256k1::new();
+        let key = PrivateKey::new(SecretKey::from_slice(&[0x01; 32]).unwrap(), Network::Bitcoin);
+        let other = PrivateKey::new(SecretKey::from_slice(&[0x02; 32]).unwrap(), Network::Bitcoin);
+        let pubkey = key.public_key(&secp);
+        let p2pkh = ScriptBuf::new_p2pkh(&pubkey.pubkey_hash());
+        let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+
+        let template = TransactionTemplate {
+            inputs: vec![(outpoint, p2pkh.clone(), Amount::from_sat(100_000), None)],
+            outputs: vec![
+                (p2pkh.clone(), Amount::from_sat(60_000)),
+                (ScriptBuf::new_op_return(&[0x01; 4]), Amount::ZERO),
+            ],
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+        };
+
+        let unsigned = template.clone().build_unsigned();
+        assert_eq!(unsigned.input.len(), 1);
+        assert_eq!(unsigned.input[0].previous_output, outpoint);
+        assert!(unsigned.input[0].script_sig.is_empty());
+        assert_eq!(unsigned.output.len(), 2);
+        assert_eq!(unsigned.output[0].value, Amount::from_sat(60_000));
+
+        let signed = template.clone().build_signed(&[other, key], &secp).unwrap();
+        assert_eq!(signed.output, unsigned.output);
+        let pushes = signed.input[0].script_sig_pushes().unwrap();
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(pushes[1], pubkey.to_bytes());
+
+        let sig = ecdsa::Signature::from_slice(&pushes[0]).unwrap();
+        let sighash = SighashCache::new(&unsigned).legacy_signature_hash(0, &p2pkh, 1).unwrap();
+        let msg = Message::from_digest(sighash.to_byte_array());
+        secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).unwrap();
+
+        assert_eq!(template.build_signed(&[other], &secp), Err(SignInputError::KeyMismatch));
+    }
+
+    #[test]
+    fn dust_value() {
+        use crate::crypto::key::{PubkeyHash, WPubkeyHash};
+
+        let low = FeeRate::from_sat_per_vb_unchecked(1);
+        let default = FeeRate::from_sat_per_vb_unchecked(3);
+
+        let p2pkh = TxOut {
+            value: Amount::from_sat(546),
+            script_pubkey: ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+        };
+        assert_eq!(p2pkh.dust_value(low), Amount::from_sat(182));
+        assert_eq!(p2pkh.dust_value(default), Amount::from_sat(546));
+        assert_eq!(p2pkh.dust_value(default), p2pkh.script_pubkey.dust_value());
+        assert!(!p2pkh.is_dust(default));
+        assert!(TxOut { value: Amount::from_sat(545), ..p2pkh.clone() }.is_dust(default));
+
+        let p2wpkh = TxOut {
+            value: Amount::from_sat(200),
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+        };
+        assert_eq!(p2wpkh.dust_value(low), Amount::from_sat(98));
+        assert_eq!(p2wpkh.dust_value(default), Amount::from_sat(294));
+        assert_eq!(p2wpkh.dust_value(default), p2wpkh.script_pubkey.dust_value());
+        assert!(!p2wpkh.is_dust(low));
+        assert!(p2wpkh.is_dust(default));
+
+        let op_return =
+            TxOut { value: Amount::ZERO, script_pubkey: ScriptBuf::new_op_return(&[0u8; 0]) };
+        assert!(!op_return.is_dust(default));
+    }
+
+    #[test]
+    fn legacy_sighash() {
+        use crate::crypto::sighash::LegacySighash;
+
+        // Transaction and script code from `legacy_sighash.json`, with the standard sighash types.
+        let tx: Transaction = deserialize(&hex!("ed3bb93802ddbd08cb030ef60a2247f715a0226de390c9c1a81d52e83f8674879065b5f87d0300000003ab6552ffffffff04d2c5e60a21fb6da8de20bf206db43b720e2a24ce26779bca25584c3f765d1e0200000008ab656a6aacab00ab6e946ded025a811d04000000000951abac6352ac00ab5143cfa3030000000005635200636a00000000")).unwrap();
+        let script_code = ScriptBuf::from_hex("5352ac650065535300").unwrap();
+
+        let vectors = [
+            (
+                EcdsaSighashType::All,
+                "661177f896383e6ea978a1cbe41f0419095ab1b290e78599ac03c3bf7fbf5f7a",
+            ),
+            (
+                EcdsaSighashType::None,
+                "21ba64f3a551579ddd8968212f4a12b3ceac095eb2513caf232efbda0a2d5203",
+            ),
+            (
+                EcdsaSighashType::Single,
+                "674ce9c675cafe108677a4b49a232863b823a891012a7e3ea1c36c21bae0a231",
+            ),
+            (
+                EcdsaSighashType::AllPlusAnyoneCanPay,
+                "4e4d409448dda6f29068e73db38e58539e2083229149780d72e8585138211dd5",
+            ),
+            (
+                EcdsaSighashType::NonePlusAnyoneCanPay,
+                "de3b32cbd5a8edf2ecbc00e701a6ed87fa3a2cd20dc4812be7b0ddf0fe9eb5d1",
+            ),
+            (
+                EcdsaSighashType::SinglePlusAnyoneCanPay,
+                "5f13fa9bf9aeb379ad30b9885e203b9039b3f61687cd72521f5eb998ee18ca81",
+            ),
+        ];
+        for (sighash_type, expected) in vectors.iter() {
+            let expected = LegacySighash::from_slice(&hex!(expected)).unwrap();
+            assert_eq!(tx.legacy_sighash(1, &script_code, *sighash_type), Ok(expected));
+        }
+
+        // The `SIGHASH_SINGLE` bug, there is no third output.
+        let mut more_inputs = tx.clone();
+        more_inputs.input.push(TxIn::default());
+        let one = LegacySighash::from_byte_array(crate::crypto::sighash::UINT256_ONE);
+        let single = [EcdsaSighashType::Single, EcdsaSighashType::SinglePlusAnyoneCanPay];
+        for sighash_type in single.iter() {
+            assert_eq!(more_inputs.legacy_sighash(2, &script_code, *sighash_type), Ok(one));
+        }
+        assert_ne!(more_inputs.legacy_sighash(2, &script_code, EcdsaSighashType::All), Ok(one));
+
+        assert_eq!(
+            tx.legacy_sighash(2, &script_code, EcdsaSighashType::All),
+            Err(sighash::Error::IndexOutOfInputsBounds { index: 2, inputs_size: 2 })
+        );
+    }
+
+    #[test]
+    fn coinbase_spendable_at() {
+        let genesis = crate::blockdata::constants::genesis_block(Network::Bitcoin);
+        let coinbase = &genesis.txdata[0];
+        assert_eq!(coinbase.coinbase_spendable_at(0, Network::Bitcoin), 100);
+        assert_eq!(coinbase.coinbase_spendable_at(1_000, Network::Regtest), 1_100);
+        assert_eq!(coinbase.coinbase_spendable_at(u32::MAX, Network::Bitcoin), u32::MAX);
+    }
+
+    #[test]
+    fn txout_new_op_return() {
+        let empty = TxOut::new_op_return(&[]).unwrap();
+        assert_eq!(empty.value, Amount::ZERO);
+        assert_eq!(empty.script_pubkey.as_bytes(), &[0x6a, 0x00]);
+
+        let max = TxOut::new_op_return(&[0xab; 80]).unwrap();
+        assert_eq!(max.value, Amount::ZERO);
+        assert!(max.script_pubkey.is_op_return());
+        assert_eq!(max.script_pubkey.len(), MAX_OP_RETURN_RELAY as usize);
+        assert_eq!(&max.script_pubkey.as_bytes()[..3], &[0x6a, 0x4c, 80]);
+
+        assert_eq!(
+            TxOut::new_op_return(&[0xab; 81]),
+            Err(OpReturnError::DataTooLong { len: 81, max: 80 })
+        );
+    }
+
+    #[test]
+    fn lock_time_enforcement() {
+        let height = Height::from_consensus(1_000).unwrap();
+        let time = Time::from_consensus(1_700_000_000).unwrap();
+        let input = |sequence| TxIn { sequence, ..Default::default() };
+
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::from_height(2_000).unwrap(),
+            input: vec![input(Sequence::MAX), input(Sequence::MAX)],
+            output: vec![],
+        };
+        // All inputs final, nLockTime is ignored.
+        assert!(!tx.is_lock_time_enabled());
+        assert!(tx.is_absolute_timelock_satisfied(height, time));
+
+        tx.input[1].sequence = Sequence::ENABLE_LOCKTIME_NO_RBF;
+        assert!(tx.is_lock_time_enabled());
+        assert!(!tx.is_absolute_timelock_satisfied(height, time));
+        assert!(tx.is_absolute_timelock_satisfied(Height::from_consensus(2_000).unwrap(), time));
+
+        tx.lock_time = absolute::LockTime::from_time(1_800_000_000).unwrap();
+        assert!(!tx.is_absolute_timelock_satisfied(height, time));
+        tx.lock_time = absolute::LockTime::from_time(1_600_000_000).unwrap();
+        assert!(tx.is_absolute_timelock_satisfied(height, time));
+    }
+
+    #[test]
+    fn locktime_satisfaction() {
+        let time = Time::from_consensus(1_700_000_000).unwrap();
+        let input = |sequence| TxIn { sequence, ..Default::default() };
+
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::from_height(1_000).unwrap(),
+            input: vec![input(Sequence::ENABLE_LOCKTIME_NO_RBF)],
+            output: vec![],
+        };
+        assert_eq!(tx.locktime_satisfaction_height(), Some(1_000));
+        assert_eq!(tx.locktime_satisfaction_time(), None);
+        let height = Height::from_consensus(1_000).unwrap();
+        assert!(tx.is_absolute_timelock_satisfied(height, time));
+        let height = Height::from_consensus(999).unwrap();
+        assert!(!tx.is_absolute_timelock_satisfied(height, time));
+
+        tx.lock_time = absolute::LockTime::from_time(1_700_000_000).unwrap();
+        assert_eq!(tx.locktime_satisfaction_height(), None);
+        assert_eq!(tx.locktime_satisfaction_time(), Some(1_700_000_000));
+
+        tx.lock_time = absolute::LockTime::ZERO;
+        tx.input = vec![input(Sequence::MAX), input(Sequence::MAX)];
+        assert_eq!(tx.locktime_satisfaction_height(), None);
+        assert_eq!(tx.locktime_satisfaction_time(), None);
+
+        // Not enabled since all sequences are final.
+        tx.lock_time = absolute::LockTime::from_height(1_000).unwrap();
+        assert_eq!(tx.locktime_satisfaction_height(), None);
+    }
+
+    #[test]
+    fn sort_bip69() {
+        let input = |txid: &str, vout| TxIn {
+            previous_output: OutPoint::new(txid.parse().unwrap(), vout),
+            ..Default::default()
+        };
+        let output = |value, script: &str| TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: ScriptBuf::from_hex(script).unwrap(),
+        };
+        let low = "0000000000000000000000000000000000000000000000000000000000000001";
+        let high = "0100000000000000000000000000000000000000000000000000000000000000";
+
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![input(high, 0), input(low, 1), input(low, 0)],
+            output: vec![output(2_000, "51"), output(1_000, "52"), output(1_000, "51")],
+        };
+        assert!(!tx.is_bip69_sorted());
+
+        tx.sort_bip69();
+        assert!(tx.is_bip69_sorted());
+        // Txids compare in displayed byte order, not in internal byte order.
+        assert_eq!(tx.input, vec![input(low, 0), input(low, 1), input(high, 0)]);
+        assert_eq!(tx.output, vec![output(1_000, "51"), output(1_000, "52"), output(2_000, "51")]);
+    }
+
+    #[test]
+    fn check_duplicate_inputs() {
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let outpoint = tx.input[0].previous_output;
+        let other = OutPoint::new(outpoint.txid, outpoint.vout + 1);
+        tx.input.push(TxIn { previous_output: other, ..Default::default() });
+        assert_eq!(tx.check_duplicate_inputs(), Ok(()));
+
+        tx.input.push(TxIn { previous_output: outpoint, ..Default::default() });
+        assert_eq!(tx.input.len(), 3);
+        assert_eq!(
+            tx.check_duplicate_inputs(),
+            Err(DuplicateInputError { duplicated_outpoint: outpoint })
+        );
+    }
+
+    #[test]
+    fn script_sig_pushes() {
+        let tx: Transaction = deserialize(&hex!("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000")).unwrap();
+        let pushes = tx.input[0].script_sig_pushes().unwrap();
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(pushes[0].len(), 73);
+        assert_eq!(pushes[0].last(), Some(&0x01)); // SIGHASH_ALL
+        assert_eq!(
+            pushes[1],
+            hex!("033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52")
+        );
+
+        let genesis = constants::genesis_block(Network::Bitcoin);
+        let pushes = genesis.txdata[0].input[0].script_sig_pushes().unwrap();
+        assert_eq!(pushes.len(), 3);
+        assert_eq!(pushes[0], hex!("ffff001d"));
+        assert_eq!(pushes[1], [0x04]);
+        assert_eq!(
+            pushes[2],
+            b"Six Flags coaster victim concerned about seat. USAToday - 07.20.2013".to_vec()
+        );
+
+        let script_sig = Builder::new().push_int(0).push_int(-1).push_int(16).into_script();
+        let mut input = TxIn { script_sig, ..Default::default() };
+        assert_eq!(input.script_sig_pushes().unwrap(), vec![vec![], vec![0x81], vec![0x10]]);
+
+        input.script_sig = Builder::new()
+            .push_slice([1u8, 2])
+            .push_opcode(crate::blockdata::opcodes::all::OP_CHECKSIG)
+            .into_script();
+        assert_eq!(
+            input.script_sig_pushes(),
+            Err(SigPushOnlyError::NonPushOpcode {
+                index: 3,
+                opcode: crate::blockdata::opcodes::all::OP_CHECKSIG
+            })
+        );
+    }
+
+    #[test]
+    fn size_breakdown() {
+        let genesis_tx = &constants::genesis_block(Network::Bitcoin).txdata[0];
+        let breakdown = genesis_tx.size_breakdown();
+        assert_eq!(breakdown.total(), serialize(genesis_tx).len());
+        assert_eq!(breakdown.inputs.len(), 1);
+        assert_eq!(breakdown.inputs[0].witness, 0);
+        assert_eq!(breakdown.outputs[0].value, 8);
+        let script_pubkey_len = genesis_tx.output[0].script_pubkey.len();
+        assert_eq!(breakdown.outputs[0].script_pubkey, script_pubkey_len + 1);
+
+        let segwit_tx: Transaction = deserialize(&hex!(
+            "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c01000000\
+            00ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022\
+            100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd271\
+            0e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed89410\
+            55d3bcb8627d085e94553e62f057dcc00000000"
+        ))
+        .unwrap();
+        let breakdown = segwit_tx.size_breakdown();
+        assert_eq!(breakdown.total(), serialize(&segwit_tx).len());
+        assert_eq!(breakdown.total(), segwit_tx.total_size());
+        assert_eq!(breakdown.inputs[0].witness, segwit_tx.input[0].witness.size());
+    }
+
+    #[test]
+    fn sequence_constants() {
+        let genesis = constants::genesis_block(Network::Bitcoin);
+        assert_eq!(genesis.txdata[0].input[0].sequence, Sequence::COINBASE);
+        assert_eq!(Sequence::COINBASE, Sequence(0xFFFFFFFF));
+        assert_eq!(Sequence::FINAL, Sequence(0xFFFFFFFF));
+        assert!(Sequence::FINAL.is_final());
+        assert_eq!(Sequence::ENABLE_LOCKTIME_NO_RBF, Sequence(0xFFFFFFFE));
+        assert_eq!(Sequence::ENABLE_RBF_NO_LOCKTIME, Sequence(0xFFFFFFFD));
+    }
+
+    #[test]
+    fn verify_not_coinbase() {
+        let coinbase = constants::genesis_block(Network::Bitcoin).txdata[0].clone();
+        assert_eq!(coinbase.verify_not_coinbase(), Err(UnexpectedCoinbaseError::IsCoinbase));
+        assert!(!coinbase.has_null_outpoint_non_coinbase());
+
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert_eq!(tx.verify_not_coinbase(), Ok(()));
+        assert!(!tx.has_null_outpoint_non_coinbase());
+
+        tx.input.push(TxIn { previous_output: OutPoint::null(), ..Default::default() });
+        assert_eq!(tx.verify_not_coinbase(), Ok(()));
+        assert!(tx.has_null_outpoint_non_coinbase());
+    }
+
     #[test]
     fn nonsegwit_transaction() {
         let tx_bytes = hex!("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000");
@@ -1626,6 +3378,57 @@ mod tests {
         assert_eq!(tx_without_witness.total_size(), expected_strippedsize);
     }
 
+    #[test]
+    fn txid_wtxid() {
+        let tx_bytes = hex!(
+            "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c01000000\
+            00ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022\
+            100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd271\
+            0e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed89410\
+            55d3bcb8627d085e94553e62f057dcc00000000"
+        );
+        let mut tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert_ne!(tx.txid().to_byte_array(), tx.wtxid().to_byte_array());
+
+        let txid = tx.txid();
+        tx.input[0].witness.clear();
+        assert_eq!(tx.txid(), txid);
+        assert_eq!(tx.txid().to_byte_array(), tx.wtxid().to_byte_array());
+
+        // The coinbase placeholder used in the witness commitment.
+        assert_eq!(Wtxid::all_zeros().to_byte_array(), [0u8; 32]);
+    }
+
+    #[test]
+    fn segwit_serialization() {
+        use crate::blockdata::constants;
+
+        let genesis_tx = &constants::genesis_block(Network::Bitcoin).txdata[0];
+        assert!(!genesis_tx.is_segwit());
+        assert_eq!(genesis_tx.legacy_serialization_size(), serialize(genesis_tx).len());
+        // Marker, flag and one empty witness.
+        assert_eq!(
+            genesis_tx.segwit_serialization_size(),
+            genesis_tx.legacy_serialization_size() + 3
+        );
+
+        let tx_bytes = hex!(
+            "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c01000000\
+            00ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022\
+            100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd271\
+            0e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed89410\
+            55d3bcb8627d085e94553e62f057dcc00000000"
+        );
+        let mut tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert!(tx.is_segwit());
+        assert_eq!(tx.segwit_serialization_size(), tx_bytes.len());
+        let legacy_size = tx.legacy_serialization_size();
+
+        tx.input[0].witness.clear();
+        assert!(!tx.is_segwit());
+        assert_eq!(legacy_size, serialize(&tx).len());
+    }
+
     // We temporarily abuse `Transaction` for testing consensus serde adapter.
     #[cfg(feature = "serde")]
     #[test]
@@ -2118,6 +3921,74 @@ mod tests {
             assert_eq!(tx.total_sigop_cost(return_none), *expected_none);
         }
     }
+
+    #[test]
+    fn total_sigop_cost_p2sh_multisig() {
+        use secp256k1::{Secp256k1, SecretKey};
+
+        use crate::blockdata::script::{Builder, PushBytesBuf};
+        use crate::PublicKey;
+
+        let secp = Secp256k1::new();
+        let pubkeys = (1..=5u8)
+            .map(|i| {
+                let sk = SecretKey::from_slice(&[i; 32]).unwrap();
+                PublicKey::new(secp256k1::PublicKey::from_secret_key(&secp, &sk))
+            })
+            .collect::<Vec<_>>();
+        let (redeem_script, script_pubkey) = ScriptBuf::new_p2sh_multisig(3, &pubkeys).unwrap();
+        // Accurate mode counts the number of keys, legacy mode always 20.
+        assert_eq!(redeem_script.count_sigops(), 5);
+        assert_eq!(redeem_script.count_sigops_legacy(), 20);
+
+        let script_sig = Builder::new()
+            .push_int(0)
+            .push_slice([0u8; 72])
+            .push_slice([0u8; 72])
+            .push_slice([0u8; 72])
+            .push_slice(PushBytesBuf::try_from(redeem_script.to_bytes()).unwrap())
+            .into_script();
+        let tx = Transaction {
+            version: Version::ONE,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint { txid: Txid::all_zeros(), vout: 0 },
+                script_sig,
+                ..Default::default()
+            }],
+            output: vec![TxOut { value: Amount::from_sat(1_000), script_pubkey: ScriptBuf::new() }],
+        };
+        let prevout = TxOut { value: Amount::from_sat(2_000), script_pubkey };
+        assert_eq!(tx.total_sigop_cost(|_| Some(prevout.clone())), 5 * 4);
+        assert_eq!(tx.total_sigop_cost(|_| None), 0);
+    }
+
+    #[test]
+    fn witness_sigops_block_limit() {
+        use crate::blockdata::constants::MAX_BLOCK_SIGOPS_COST;
+
+        let tx_bytes = hex!(
+            "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c01000000\
+            00ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022\
+            100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd271\
+            0e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed89410\
+            55d3bcb8627d085e94553e62f057dcc00000000"
+        );
+        let mut tx: Transaction = deserialize(&tx_bytes).unwrap();
+        let p2wpkh = ScriptBuf::from_hex("00141c6977423aa4b82a0d7f8496cdf3fc2f8b4f580c").unwrap();
+        let prevout = TxOut { value: Amount::from_sat(1_000_000), script_pubkey: p2wpkh };
+        // The P2SH output counts no sigops, the P2WPKH input counts once and is not scaled.
+        assert_eq!(tx.total_sigop_cost(|_| Some(prevout.clone())), 1);
+
+        // No block fits more P2WPKH inputs than its weight limit divided by the input weight.
+        let input_weight = tx.input[0].segwit_weight().to_wu();
+        let max_inputs = (Weight::MAX_BLOCK.to_wu() / input_weight) as usize;
+        tx.input = vec![tx.input[0].clone(); max_inputs];
+
+        let cost = tx.total_sigop_cost(|_| Some(prevout.clone()));
+        assert_eq!(cost, max_inputs);
+        assert!(cost <= MAX_BLOCK_SIGOPS_COST as usize);
+    }
 }
 
 #[cfg(bench)]
@@ -180,12 +180,16 @@ mod tests {
     fn to_vb_floor() {
         assert_eq!(1, Weight(4).to_vbytes_floor());
         assert_eq!(1, Weight(5).to_vbytes_floor());
+        assert_eq!(99, Weight(399).to_vbytes_floor());
+        assert_eq!(100, Weight(400).to_vbytes_floor());
     }
 
     #[test]
     fn to_vb_ceil() {
         assert_eq!(1, Weight(4).to_vbytes_ceil());
         assert_eq!(2, Weight(5).to_vbytes_ceil());
+        assert_eq!(100, Weight(399).to_vbytes_ceil());
+        assert_eq!(100, Weight(400).to_vbytes_ceil());
     }
 
     #[test]
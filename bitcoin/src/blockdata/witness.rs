@@ -249,6 +249,19 @@ impl Witness {
         witness
     }
 
+    /// Creates a witness required to spend a P2WSH output.
+    ///
+    /// The witness will be made up of the `stack` items, in order, followed by the serialized
+    /// `witness_script` whose hash is committed to in the output being spent.
+    pub fn p2wsh(stack: &[&[u8]], witness_script: &Script) -> Witness {
+        let mut witness = Witness::new();
+        for item in stack {
+            witness.push_slice(item);
+        }
+        witness.push_slice(witness_script.as_bytes());
+        witness
+    }
+
     /// Creates a [`Witness`] object from a slice of bytes slices where each slice is a witness item.
     pub fn from_slice<T: AsRef<[u8]>>(slice: &[T]) -> Self {
         let witness_elements = slice.len();
@@ -278,6 +291,9 @@ impl Witness {
     pub fn to_vec(&self) -> Vec<Vec<u8>> { self.iter().map(|s| s.to_vec()).collect() }
 
     /// Returns `true` if the witness contains no element.
+    ///
+    /// This is the case for legacy inputs, note that a witness holding a single empty element is
+    /// not empty.
     pub fn is_empty(&self) -> bool { self.witness_elements == 0 }
 
     /// Returns a struct implementing [`Iterator`].
@@ -396,6 +412,9 @@ impl Witness {
         self.element_at(pos)
     }
 
+    /// Returns the stack item at `index`, if any, same as [`Witness::nth`].
+    pub fn item_at(&self, index: usize) -> Option<&[u8]> { self.nth(index) }
+
     /// Get Tapscript following BIP341 rules regarding accounting for an annex.
     ///
     /// This does not guarantee that this represents a P2TR [`Witness`]. It
@@ -651,6 +670,37 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_stack_depth() {
+        let legacy = Witness::default();
+        assert!(legacy.is_empty());
+        assert_eq!(legacy.len(), 0);
+        assert_eq!(legacy.nth(0), None);
+        assert_eq!(legacy.iter().next(), None);
+
+        let empty_item = Witness::from_slice(&[[0u8; 0]]);
+        assert!(!empty_item.is_empty());
+        assert_eq!(empty_item.len(), 1);
+        assert_eq!(empty_item.nth(0), Some(&[0u8; 0][..]));
+
+        let sig = [0x30u8; 72];
+        let pubkey = [0x02u8; 33];
+        let p2wpkh = Witness::from_slice(&[&sig[..], &pubkey[..]]);
+        assert_eq!(p2wpkh.len(), 2);
+        assert_eq!(p2wpkh.nth(0), Some(&sig[..]));
+        assert_eq!(p2wpkh.nth(1), Some(&pubkey[..]));
+        assert_eq!(p2wpkh.nth(2), None);
+        assert_eq!(p2wpkh.item_at(1), Some(&pubkey[..]));
+        assert_eq!(p2wpkh.item_at(2), None);
+        assert_eq!(p2wpkh.iter().collect::<Vec<_>>(), vec![&sig[..], &pubkey[..]]);
+
+        let witness_script = crate::ScriptBuf::from_bytes(vec![0x51]);
+        let p2wsh = Witness::p2wsh(&[&[], &sig[..]], &witness_script);
+        assert_eq!(p2wsh.len(), 3);
+        assert_eq!(p2wsh.nth(0), Some(&[0u8; 0][..]));
+        assert_eq!(p2wsh.last(), Some(witness_script.as_bytes()));
+    }
+
     #[test]
     fn test_push_ecdsa_sig() {
         // The very first signature in block 734,958
@@ -666,6 +716,57 @@ mod test {
         assert_eq!(witness.to_vec(), expected_witness);
     }
 
+    #[test]
+    fn p2wpkh_p2wsh_witness() {
+        use hashes::Hash;
+        use secp256k1::{Message, Secp256k1, SecretKey};
+
+        use crate::blockdata::opcodes::all::OP_CHECKSIG;
+        use crate::blockdata::script::{Builder, ScriptBuf};
+        use crate::blockdata::transaction::{OutPoint, TxIn, TxOut};
+        use crate::sighash::SighashCache;
+        use crate::{absolute, transaction, Amount, PublicKey};
+
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let pk = PublicKey::new(secp256k1::PublicKey::from_secret_key(&secp, &sk));
+        let spk = ScriptBuf::new_p2wpkh(&pk.wpubkey_hash().unwrap());
+        let value = Amount::from_sat(50_000);
+
+        let mut tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn { previous_output: OutPoint::null(), ..Default::default() }],
+            output: vec![TxOut { value: Amount::from_sat(49_000), script_pubkey: spk.clone() }],
+        };
+        let sighash = SighashCache::new(&tx)
+            .p2wpkh_signature_hash(0, &spk, value, EcdsaSighashType::All)
+            .unwrap();
+        let msg = Message::from_digest(sighash.to_byte_array());
+        let sig = ecdsa::Signature::sighash_all(secp.sign_ecdsa(&msg, &sk));
+        tx.input[0].witness = Witness::p2wpkh(&sig, &pk.inner);
+
+        // Verify the witness against the output it spends, as the interpreter would.
+        let witness = &tx.input[0].witness;
+        assert_eq!(witness.len(), 2);
+        let parsed_sig = ecdsa::Signature::from_slice(&witness[0]).unwrap();
+        let parsed_pk = PublicKey::from_slice(&witness[1]).unwrap();
+        assert_eq!(parsed_sig.hash_ty, EcdsaSighashType::All);
+        assert_eq!(ScriptBuf::new_p2wpkh(&parsed_pk.wpubkey_hash().unwrap()), spk);
+        assert!(secp.verify_ecdsa(&msg, &parsed_sig.sig, &parsed_pk.inner).is_ok());
+
+        let witness_script = Builder::new().push_key(&pk).push_opcode(OP_CHECKSIG).into_script();
+        let sig_bytes = sig.to_vec();
+        let witness = Witness::p2wsh(&[&sig_bytes], &witness_script);
+        assert_eq!(witness.len(), 2);
+        assert_eq!(&witness[0], sig_bytes.as_slice());
+        assert_eq!(witness.last(), Some(witness_script.as_bytes()));
+        assert_eq!(
+            ScriptBuf::new_p2wsh(&Script::from_bytes(witness.last().unwrap()).wscript_hash()),
+            witness_script.to_p2wsh()
+        );
+    }
+
     #[test]
     fn test_witness() {
         let w0 = hex!("03d2e15674941bad4a996372cb87e1856d3652606d98562fe39c5e9e7e413f2105");
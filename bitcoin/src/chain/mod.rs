@@ -0,0 +1,10 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Block header chain.
+//!
+//! This module provides storage for the chain of block headers downloaded by an indexer.
+//!
+
+pub mod store;
+
+pub use self::store::{HeaderStore, MemoryHeaderStore, StoreError};
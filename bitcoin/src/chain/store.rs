@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Header chain storage.
+//!
+//! This module defines [`HeaderStore`], the interface used to store and look up block headers
+//! during initial block download, and [`MemoryHeaderStore`], an in-memory implementation.
+//!
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::blockdata::block;
+use crate::hash_types::BlockHash;
+
+/// Storage for a chain of block headers.
+///
+/// Headers are appended one at a time in chain order, the first header pushed may be at any
+/// height (e.g. a checkpoint) and every following one must extend the tip.
+pub trait HeaderStore {
+    /// Returns the header with the given block hash, if it is in the store.
+    fn get_header(&self, hash: &BlockHash) -> Option<block::Header>;
+
+    /// Returns the header at `height`, if it is in the store.
+    fn get_header_at_height(&self, height: u32) -> Option<block::Header>;
+
+    /// Returns the block hash and height of the last header pushed, or `None` if the store is
+    /// empty.
+    fn tip(&self) -> Option<(BlockHash, u32)>;
+
+    /// Appends `header` at `height`.
+    ///
+    /// # Errors
+    ///
+    /// If the store is not empty and `height` is not one above the tip or `header` does not
+    /// commit to the tip as its previous block.
+    fn push_header(&mut self, header: block::Header, height: u32) -> Result<(), StoreError>;
+}
+
+/// A [`HeaderStore`] keeping all headers in memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryHeaderStore {
+    headers: HashMap<BlockHash, (block::Header, u32)>,
+    heights: HashMap<u32, BlockHash>,
+    tip: Option<(BlockHash, u32)>,
+}
+
+impl MemoryHeaderStore {
+    /// Creates an empty header store.
+    pub fn new() -> Self { MemoryHeaderStore::default() }
+
+    /// Returns the number of headers in the store.
+    pub fn len(&self) -> usize { self.headers.len() }
+
+    /// Returns `true` if the store contains no headers.
+    pub fn is_empty(&self) -> bool { self.headers.is_empty() }
+}
+
+impl HeaderStore for MemoryHeaderStore {
+    fn get_header(&self, hash: &BlockHash) -> Option<block::Header> {
+        self.headers.get(hash).map(|(header, _)| header.clone())
+    }
+
+    fn get_header_at_height(&self, height: u32) -> Option<block::Header> {
+        self.heights.get(&height).and_then(|hash| self.get_header(hash))
+    }
+
+    fn tip(&self) -> Option<(BlockHash, u32)> { self.tip }
+
+    fn push_header(&mut self, header: block::Header, height: u32) -> Result<(), StoreError> {
+        if let Some((tip_hash, tip_height)) = self.tip {
+            let expected = tip_height.checked_add(1).ok_or(StoreError::HeightOverflow)?;
+            if height != expected {
+                return Err(StoreError::UnexpectedHeight { expected, got: height });
+            }
+            if header.prev_blockhash != tip_hash {
+                return Err(StoreError::DoesNotConnect {
+                    tip: tip_hash,
+                    prev_blockhash: header.prev_blockhash,
+                });
+            }
+        }
+
+        let hash = header.block_hash();
+        self.headers.insert(hash, (header, height));
+        self.heights.insert(height, hash);
+        self.tip = Some((hash, height));
+        Ok(())
+    }
+}
+
+/// An error pushing a header to a [`HeaderStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StoreError {
+    /// The header is not at the height following the tip.
+    UnexpectedHeight {
+        /// The height following the tip.
+        expected: u32,
+        /// The height the header was pushed at.
+        got: u32,
+    },
+    /// The header does not extend the tip.
+    DoesNotConnect {
+        /// The block hash of the tip.
+        tip: BlockHash,
+        /// The previous block hash committed to by the header.
+        prev_blockhash: BlockHash,
+    },
+    /// The tip is at the maximum height.
+    HeightOverflow,
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use StoreError::*;
+
+        match *self {
+            UnexpectedHeight { expected, got } =>
+                write!(f, "header pushed at height {}, expected height {}", got, expected),
+            DoesNotConnect { ref tip, ref prev_blockhash } => write!(
+                f,
+                "header with previous block {} does not extend the tip {}",
+                prev_blockhash, tip
+            ),
+            HeightOverflow => f.write_str("header store tip is at the maximum height"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use StoreError::*;
+
+        match *self {
+            UnexpectedHeight { .. } | DoesNotConnect { .. } | HeightOverflow => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockdata::constants::genesis_block;
+    use crate::network::Network;
+
+    /// Returns `count` headers building on the genesis block, which is the first.
+    fn chain(count: u32) -> Vec<block::Header> {
+        let mut headers = vec![genesis_block(Network::Bitcoin).header];
+        for i in 1..count {
+            let mut header = headers[i as usize - 1].clone();
+            header.prev_blockhash = header.block_hash();
+            header.time += 60;
+            header.nonce = i;
+            headers.push(header);
+        }
+        headers
+    }
+
+    #[test]
+    fn push_then_get_round_trip() {
+        // Start from the genesis block and from a checkpoint.
+        for start in [0, 1, 500_000, u32::MAX - 20].iter() {
+            let headers = chain(20);
+            let mut store = MemoryHeaderStore::new();
+            assert_eq!(store.tip(), None);
+
+            for (i, header) in headers.iter().enumerate() {
+                let height = start + i as u32;
+                store.push_header(header.clone(), height).unwrap();
+                assert_eq!(store.tip(), Some((header.block_hash(), height)));
+                assert_eq!(store.len(), i + 1);
+            }
+
+            for (i, header) in headers.iter().enumerate() {
+                let height = start + i as u32;
+                assert_eq!(store.get_header(&header.block_hash()).as_ref(), Some(header));
+                assert_eq!(store.get_header_at_height(height).as_ref(), Some(header));
+            }
+            assert_eq!(store.get_header_at_height(start + headers.len() as u32), None);
+        }
+    }
+
+    #[test]
+    fn push_invalid_header() {
+        let headers = chain(3);
+        let mut store = MemoryHeaderStore::new();
+        store.push_header(headers[0].clone(), 0).unwrap();
+        let tip = store.tip();
+
+        assert_eq!(
+            store.push_header(headers[1].clone(), 2),
+            Err(StoreError::UnexpectedHeight { expected: 1, got: 2 })
+        );
+        assert_eq!(
+            store.push_header(headers[2].clone(), 1),
+            Err(StoreError::DoesNotConnect {
+                tip: headers[0].block_hash(),
+                prev_blockhash: headers[1].block_hash(),
+            })
+        );
+        assert_eq!(store.tip(), tip);
+        assert_eq!(store.get_header(&headers[2].block_hash()), None);
+
+        let mut store = MemoryHeaderStore::new();
+        store.push_header(headers[0].clone(), u32::MAX).unwrap();
+        assert_eq!(store.push_header(headers[1].clone(), 0), Err(StoreError::HeightOverflow));
+    }
+}
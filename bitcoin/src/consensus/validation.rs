@@ -3,6 +3,8 @@
 //! Transaction and script validation.
 //!
 //! Relies on the `bitcoinconsensus` crate that uses Bitcoin Core libconsensus to perform validation.
+//! This crate has no script interpreter of its own so script validation is only available with the
+//! `bitcoinconsensus` feature enabled.
 
 use core::fmt;
 
@@ -143,6 +145,39 @@ impl Script {
     ) -> Result<(), BitcoinconsensusError> {
         verify_script_with_flags(self, index, amount, spending_tx, flags)
     }
+
+    /// Verifies that `script_sig` spends `script_pubkey` as input `input_index` of `tx_bytes`.
+    ///
+    /// `script_sig` must be the scriptSig of the input, this catches a wrong `input_index` before
+    /// calling libbitcoinconsensus. No amount is passed so only legacy spends can be verified,
+    /// segwit signatures commit to the spent amount and need [`Self::verify_with_flags`].
+    ///
+    /// # Parameters
+    ///  * `script_sig` - The scriptSig of the spending input.
+    ///  * `script_pubkey` - The script of the spent output.
+    ///  * `tx_bytes` - The serialized transaction that attempts to spend the output.
+    ///  * `input_index` - The index of the spending input in the transaction.
+    ///  * `flags` - Verification flags, see [`bitcoinconsensus::VERIFY_ALL`] and similar.
+    ///
+    /// [`bitcoinconsensus::VERIFY_ALL`]: https://docs.rs/bitcoinconsensus/0.20.2-0.5.0/bitcoinconsensus/constant.VERIFY_ALL.html
+    pub fn verify_with_bitcoinconsensus<F: Into<u32>>(
+        script_sig: &Script,
+        script_pubkey: &Script,
+        tx_bytes: &[u8],
+        input_index: usize,
+        flags: F,
+    ) -> Result<(), ConsensusError> {
+        let tx: Transaction = encode::deserialize(tx_bytes).map_err(ConsensusError::Decode)?;
+        let input = tx.input.get(input_index).ok_or(ConsensusError::InputIndexOutOfRange {
+            index: input_index,
+            inputs: tx.input.len(),
+        })?;
+        if input.script_sig.as_script() != script_sig {
+            return Err(ConsensusError::ScriptSigMismatch { index: input_index });
+        }
+        verify_script_with_flags(script_pubkey, input_index, Amount::ZERO, tx_bytes, flags)
+            .map_err(ConsensusError::Verification)
+    }
 }
 
 impl Transaction {
@@ -202,6 +237,59 @@ impl From<bitcoinconsensus::Error> for BitcoinconsensusError {
     fn from(e: bitcoinconsensus::Error) -> Self { Self(e) }
 }
 
+/// An error verifying a script, see [`Script::verify_with_bitcoinconsensus`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConsensusError {
+    /// The spending transaction could not be decoded.
+    Decode(encode::Error),
+    /// The transaction has no input at `index`.
+    InputIndexOutOfRange {
+        /// The index of the input to verify.
+        index: usize,
+        /// The number of inputs of the transaction.
+        inputs: usize,
+    },
+    /// The scriptSig is not the one of the input at `index`.
+    ScriptSigMismatch {
+        /// The index of the input to verify.
+        index: usize,
+    },
+    /// Error validating the script with bitcoinconsensus library.
+    Verification(BitcoinconsensusError),
+}
+
+impl fmt::Display for ConsensusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ConsensusError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "failed to decode the spending transaction"; e),
+            InputIndexOutOfRange { index, inputs } => write!(
+                f,
+                "input index {} out of range, the transaction has {} inputs",
+                index, inputs
+            ),
+            ScriptSigMismatch { index } =>
+                write!(f, "scriptSig does not match the one of input {}", index),
+            Verification(ref e) => write_err!(f, "bitcoinconsensus verification failed"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConsensusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ConsensusError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            Verification(ref e) => Some(e),
+            InputIndexOutOfRange { .. } | ScriptSigMismatch { .. } => None,
+        }
+    }
+}
+
 /// An error during transaction validation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -238,3 +326,65 @@ impl std::error::Error for TxVerifyError {
 impl From<BitcoinconsensusError> for TxVerifyError {
     fn from(e: BitcoinconsensusError) -> Self { TxVerifyError::ScriptVerification(e) }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex::test_hex_unwrap as hex;
+
+    use super::*;
+    use crate::blockdata::script::ScriptBuf;
+    use crate::consensus::encode::{deserialize, serialize};
+
+    #[test]
+    fn verify_with_bitcoinconsensus() {
+        // The legacy P2PKH input of the transaction in the `transaction_verify` test of
+        // `blockdata::transaction`.
+        let spending: Transaction = deserialize(hex!("020000000001031cfbc8f54fbfa4a33a30068841371f80dbfe166211242213188428f437445c91000000006a47304402206fbcec8d2d2e740d824d3d36cc345b37d9f65d665a99f5bd5c9e8d42270a03a8022013959632492332200c2908459547bf8dbf97c65ab1a28dec377d6f1d41d3d63e012103d7279dfb90ce17fe139ba60a7c41ddf605b25e1c07a4ddcb9dfef4e7d6710f48feffffff476222484f5e35b3f0e43f65fc76e21d8be7818dd6a989c160b1e5039b7835fc00000000171600140914414d3c94af70ac7e25407b0689e0baa10c77feffffffa83d954a62568bbc99cc644c62eb7383d7c2a2563041a0aeb891a6a4055895570000000017160014795d04cc2d4f31480d9a3710993fbd80d04301dffeffffff06fef72f000000000017a91476fd7035cd26f1a32a5ab979e056713aac25796887a5000f00000000001976a914b8332d502a529571c6af4be66399cd33379071c588ac3fda0500000000001976a914fc1d692f8de10ae33295f090bea5fe49527d975c88ac522e1b00000000001976a914808406b54d1044c429ac54c0e189b0d8061667e088ac6eb68501000000001976a914dfab6085f3a8fb3e6710206a5a959313c5618f4d88acbba20000000000001976a914eb3026552d7e3f3073457d0bee5d4757de48160d88ac0002483045022100bee24b63212939d33d513e767bc79300051f7a0d433c3fcf1e0e3bf03b9eb1d70220588dc45a9ce3a939103b4459ce47500b64e23ab118dfc03c9caa7d6bfc32b9c601210354fd80328da0f9ae6eef2b3a81f74f9a6f66761fadf96f1d1d22b1fd6845876402483045022100e29c7e3a5efc10da6269e5fc20b6a1cb8beb92130cc52c67e46ef40aaa5cac5f0220644dd1b049727d991aece98a105563416e10a5ac4221abac7d16931842d5c322012103960b87412d6e169f30e12106bdf70122aabb9eb61f455518322a18b920a4dfa887d30700")
+            .as_slice()).unwrap();
+        let spent: Transaction = deserialize(hex!("020000000001040aacd2c49f5f3c0968cfa8caf9d5761436d95385252e3abb4de8f5dcf8a582f20000000017160014bcadb2baea98af0d9a902e53a7e9adff43b191e9feffffff96cd3c93cac3db114aafe753122bd7d1afa5aa4155ae04b3256344ecca69d72001000000171600141d9984579ceb5c67ebfbfb47124f056662fe7adbfeffffffc878dd74d3a44072eae6178bb94b9253177db1a5aaa6d068eb0e4db7631762e20000000017160014df2a48cdc53dae1aba7aa71cb1f9de089d75aac3feffffffe49f99275bc8363f5f593f4eec371c51f62c34ff11cc6d8d778787d340d6896c0100000017160014229b3b297a0587e03375ab4174ef56eeb0968735feffffff03360d0f00000000001976a9149f44b06f6ee92ddbc4686f71afe528c09727a5c788ac24281b00000000001976a9140277b4f68ff20307a2a9f9b4487a38b501eb955888ac227c0000000000001976a9148020cd422f55eef8747a9d418f5441030f7c9c7788ac0247304402204aa3bd9682f9a8e101505f6358aacd1749ecf53a62b8370b97d59243b3d6984f02200384ad449870b0e6e89c92505880411285ecd41cf11e7439b973f13bad97e53901210205b392ffcb83124b1c7ce6dd594688198ef600d34500a7f3552d67947bbe392802473044022033dfd8d190a4ae36b9f60999b217c775b96eb10dee3a1ff50fb6a75325719106022005872e4e36d194e49ced2ebcf8bb9d843d842e7b7e0eb042f4028396088d292f012103c9d7cbf369410b090480de2aa15c6c73d91b9ffa7d88b90724614b70be41e98e0247304402207d952de9e59e4684efed069797e3e2d993e9f98ec8a9ccd599de43005fe3f713022076d190cc93d9513fc061b1ba565afac574e02027c9efbfa1d7b71ab8dbb21e0501210313ad44bc030cc6cb111798c2bf3d2139418d751c1e79ec4e837ce360cc03b97a024730440220029e75edb5e9413eb98d684d62a077b17fa5b7cc19349c1e8cc6c4733b7b7452022048d4b9cae594f03741029ff841e35996ef233701c1ea9aa55c301362ea2e2f68012103590657108a72feb8dc1dec022cf6a230bb23dc7aaa52f4032384853b9f8388baf9d20700")
+            .as_slice()).unwrap();
+        assert_eq!(spending.input[0].previous_output, OutPoint::new(spent.txid(), 0));
+
+        let tx_bytes = serialize(&spending);
+        let script_sig = &spending.input[0].script_sig;
+        let spent_output = &spent.output[0];
+        let flags = bitcoinconsensus::VERIFY_ALL;
+        let verify = |script_sig: &Script, tx_bytes: &[u8], index: usize| {
+            Script::verify_with_bitcoinconsensus(
+                script_sig,
+                &spent_output.script_pubkey,
+                tx_bytes,
+                index,
+                flags,
+            )
+        };
+        let verify_output = |tx_bytes: &[u8]| {
+            spent_output.script_pubkey.verify_with_flags(0, spent_output.value, tx_bytes, flags)
+        };
+
+        // Same results as verifying the spent output directly.
+        assert!(verify(script_sig, &tx_bytes, 0).is_ok());
+        assert!(verify_output(&tx_bytes).is_ok());
+
+        let mut corrupted = spending.clone();
+        let mut sig = corrupted.input[0].script_sig.to_bytes();
+        sig[10] ^= 1;
+        corrupted.input[0].script_sig = ScriptBuf::from_bytes(sig);
+        let corrupted_bytes = serialize(&corrupted);
+        assert!(matches!(
+            verify(&corrupted.input[0].script_sig, &corrupted_bytes, 0),
+            Err(ConsensusError::Verification(_))
+        ));
+        assert!(verify_output(&corrupted_bytes).is_err());
+
+        assert!(matches!(
+            verify(Script::new(), &tx_bytes, 0),
+            Err(ConsensusError::ScriptSigMismatch { index: 0 })
+        ));
+        assert!(matches!(
+            verify(script_sig, &tx_bytes, 3),
+            Err(ConsensusError::InputIndexOutOfRange { index: 3, inputs: 3 })
+        ));
+        assert!(matches!(verify(script_sig, &tx_bytes[..10], 0), Err(ConsensusError::Decode(_))));
+    }
+}
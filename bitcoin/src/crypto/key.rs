@@ -16,6 +16,7 @@ use internals::write_err;
 pub use secp256k1::rand;
 pub use secp256k1::{self, constants, Keypair, Parity, Secp256k1, Verification, XOnlyPublicKey};
 
+use crate::address::{self, Address};
 use crate::crypto::ecdsa;
 use crate::network::Network;
 use crate::prelude::*;
@@ -67,6 +68,26 @@ impl PublicKey {
         }
     }
 
+    /// Creates a pay to public key hash (P2PKH) address for this key.
+    ///
+    /// This is equivalent to [`Address::p2pkh`].
+    #[doc(alias = "to_p2pkh_address")]
+    pub fn p2pkh_address(&self, network: Network) -> Address { Address::p2pkh(self, network) }
+
+    /// Creates a pay to witness public key hash (P2WPKH) address for this key.
+    ///
+    /// This is equivalent to [`Address::p2wpkh`] and fails if the key is uncompressed.
+    #[doc(alias = "to_p2wpkh_address")]
+    pub fn p2wpkh_address(&self, network: Network) -> Result<Address, address::Error> {
+        Address::p2wpkh(self, network)
+    }
+
+    /// Returns the x-only public key, dropping the parity of the y-coordinate.
+    ///
+    /// This is the BIP340 key used as the Taproot internal key, to tweak it use
+    /// [`TapTweak::tap_tweak`].
+    pub fn to_x_only_pubkey(&self) -> XOnlyPublicKey { self.inner.x_only_public_key().0 }
+
     /// Write the public key into a writer
     pub fn write_into<W: io::Write>(&self, mut writer: W) -> Result<(), io::Error> {
         self.with_serialized(|bytes| writer.write_all(bytes))
@@ -189,6 +210,41 @@ impl PublicKey {
         Ok(PublicKey { compressed, inner: secp256k1::PublicKey::from_slice(data)? })
     }
 
+    /// Deserializes a 33 byte compressed public key, as required by segwit v0 inputs.
+    ///
+    /// # Errors
+    ///
+    /// [`PubkeyError::WrongFormat`] if `data` is not 33 bytes long (e.g. an uncompressed key),
+    /// [`PubkeyError::InvalidKeyPrefix`] if it does not start with `0x02` or `0x03`.
+    pub fn from_compressed_slice(data: &[u8]) -> Result<PublicKey, PubkeyError> {
+        Self::from_slice_with_format(data, 33, &[0x02, 0x03])
+    }
+
+    /// Deserializes a 65 byte uncompressed public key.
+    ///
+    /// # Errors
+    ///
+    /// [`PubkeyError::WrongFormat`] if `data` is not 65 bytes long (e.g. a compressed key),
+    /// [`PubkeyError::InvalidKeyPrefix`] if it does not start with `0x04`.
+    pub fn from_uncompressed_slice(data: &[u8]) -> Result<PublicKey, PubkeyError> {
+        Self::from_slice_with_format(data, 65, &[0x04])
+    }
+
+    fn from_slice_with_format(
+        data: &[u8],
+        expected_len: usize,
+        prefixes: &[u8],
+    ) -> Result<PublicKey, PubkeyError> {
+        if data.len() != expected_len {
+            return Err(PubkeyError::WrongFormat { got_len: data.len(), expected_len });
+        }
+        if !prefixes.contains(&data[0]) {
+            return Err(PubkeyError::InvalidKeyPrefix(data[0]));
+        }
+        let inner = secp256k1::PublicKey::from_slice(data).map_err(PubkeyError::Secp256k1)?;
+        Ok(PublicKey { compressed: expected_len == 33, inner })
+    }
+
     /// Computes the public key as supposed to be used with this secret
     pub fn from_private_key<C: secp256k1::Signing>(
         secp: &Secp256k1<C>,
@@ -756,6 +812,50 @@ impl From<hex::HexToArrayError> for Error {
     fn from(e: hex::HexToArrayError) -> Self { Error::Hex(e) }
 }
 
+/// An error deserializing a public key of a specific format, see
+/// [`PublicKey::from_compressed_slice`] and [`PublicKey::from_uncompressed_slice`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PubkeyError {
+    /// The key is not of the expected length, e.g. an uncompressed key where a compressed one is
+    /// required.
+    WrongFormat {
+        /// Length of the key.
+        got_len: usize,
+        /// Length of keys of the expected format.
+        expected_len: usize,
+    },
+    /// The first byte of the key is invalid for the expected format.
+    InvalidKeyPrefix(u8),
+    /// The key is not a valid point on the curve.
+    Secp256k1(secp256k1::Error),
+}
+
+impl fmt::Display for PubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PubkeyError::*;
+
+        match *self {
+            WrongFormat { got_len, expected_len } =>
+                write!(f, "public key is {} bytes long, expected {}", got_len, expected_len),
+            InvalidKeyPrefix(b) => write!(f, "key prefix invalid: {}", b),
+            Secp256k1(ref e) => write_err!(f, "secp256k1"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PubkeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use PubkeyError::*;
+
+        match *self {
+            Secp256k1(ref e) => Some(e),
+            WrongFormat { .. } | InvalidKeyPrefix(_) => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -764,7 +864,6 @@ mod tests {
     use secp256k1::Secp256k1;
 
     use super::*;
-    use crate::address::Address;
     use crate::io;
     use crate::network::Network::{Bitcoin, Testnet};
 
@@ -840,6 +939,90 @@ mod tests {
         assert_eq!(upk.wpubkey_hash(), None);
     }
 
+    #[test]
+    fn pubkey_from_format_slices() {
+        let compressed =
+            Vec::from_hex("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+                .unwrap();
+        let uncompressed = Vec::from_hex("042e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af191923a2964c177f5b5923ae500fca49e99492d534aa3759d6b25a8bc971b133").unwrap();
+
+        let pk = PublicKey::from_compressed_slice(&compressed).unwrap();
+        assert!(pk.compressed);
+        assert_eq!(pk, PublicKey::from_slice(&compressed).unwrap());
+        let upk = PublicKey::from_uncompressed_slice(&uncompressed).unwrap();
+        assert!(!upk.compressed);
+        assert_eq!(upk, PublicKey::from_slice(&uncompressed).unwrap());
+        assert_eq!(pk.inner, upk.inner);
+
+        assert_eq!(
+            PublicKey::from_compressed_slice(&uncompressed),
+            Err(PubkeyError::WrongFormat { got_len: 65, expected_len: 33 })
+        );
+        assert_eq!(
+            PublicKey::from_uncompressed_slice(&compressed),
+            Err(PubkeyError::WrongFormat { got_len: 33, expected_len: 65 })
+        );
+
+        let mut bad_prefix = compressed.clone();
+        bad_prefix[0] = 0x04;
+        assert_eq!(
+            PublicKey::from_compressed_slice(&bad_prefix),
+            Err(PubkeyError::InvalidKeyPrefix(0x04))
+        );
+        let mut not_on_curve = compressed;
+        not_on_curve[1..].copy_from_slice(&[0xff; 32]);
+        assert!(matches!(
+            PublicKey::from_compressed_slice(&not_on_curve),
+            Err(PubkeyError::Secp256k1(_))
+        ));
+    }
+
+    #[test]
+    fn pubkey_addresses() {
+        let pk = PublicKey::from_str(
+            "032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af",
+        )
+        .unwrap();
+        let upk = PublicKey::from_str("042e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af191923a2964c177f5b5923ae500fca49e99492d534aa3759d6b25a8bc971b133").unwrap();
+
+        // Mainnet P2PKH addresses use version byte 12.
+        let addr = pk.p2pkh_address(Bitcoin);
+        assert_eq!(addr.to_string(), "64eSfFRdAVDZLqPSTPRYTvpXCK24CXf82d");
+        assert_eq!(addr, Address::p2pkh(&pk, Bitcoin));
+        assert_eq!(upk.p2pkh_address(Testnet), Address::p2pkh(&upk, Testnet));
+
+        assert_eq!(pk.p2wpkh_address(Bitcoin).unwrap(), Address::p2wpkh(&pk, Bitcoin).unwrap());
+        assert_eq!(upk.p2wpkh_address(Bitcoin), Err(address::Error::UncompressedPubkey));
+    }
+
+    #[test]
+    fn pubkey_to_x_only() {
+        // Test vector from BIP-086, m/86'/0'/0'/0/0.
+        let internal_key = XOnlyPublicKey::from_str(
+            "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+        )
+        .unwrap();
+        let even = PublicKey::from_str(
+            "02cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+        )
+        .unwrap();
+        let odd = PublicKey::from_str(
+            "03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+        )
+        .unwrap();
+
+        assert_eq!(even.to_x_only_pubkey(), internal_key);
+        assert_eq!(odd.to_x_only_pubkey(), internal_key);
+        assert_eq!(XOnlyPublicKey::from(even), internal_key);
+
+        let secp = Secp256k1::verification_only();
+        let (output_key, _parity) = even.to_x_only_pubkey().tap_tweak(&secp, None);
+        assert_eq!(
+            output_key.to_string(),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_key_serde() {
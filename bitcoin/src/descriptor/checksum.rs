@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Descriptor checksums.
+//!
+//! Descriptors may be suffixed with `#` and an 8 character checksum, computed as in Bitcoin Core's
+//! `DescriptorChecksum`. See [BIP380] for the algorithm.
+//!
+//! [BIP380]: <https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki>
+//!
+
+use core::fmt;
+
+use crate::prelude::*;
+
+/// The characters allowed in a descriptor, in the order used to compute the checksum.
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// The characters of the checksum itself.
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The number of characters of a checksum.
+const CHECKSUM_LENGTH: usize = 8;
+
+/// Verifies the checksum of `descriptor` and returns the descriptor without it.
+///
+/// # Errors
+///
+/// If the descriptor has no `#checksum` suffix, or the checksum is not the one of the descriptor.
+pub fn verify_checksum(descriptor: &str) -> Result<&str, ChecksumError> {
+    let pos = descriptor.rfind('#').ok_or(ChecksumError::Missing)?;
+    let (body, checksum) = (&descriptor[..pos], &descriptor[pos + 1..]);
+    if checksum.len() != CHECKSUM_LENGTH {
+        return Err(ChecksumError::InvalidLength(checksum.len()));
+    }
+    if compute_checksum(body)? != checksum {
+        return Err(ChecksumError::Mismatch);
+    }
+    Ok(body)
+}
+
+/// Returns `descriptor` suffixed with `#` and its checksum.
+///
+/// # Errors
+///
+/// If the descriptor contains a character that is not allowed in descriptors.
+pub fn append_checksum(descriptor: &str) -> Result<String, ChecksumError> {
+    Ok(format!("{}#{}", descriptor, compute_checksum(descriptor)?))
+}
+
+/// Computes the checksum of `descriptor`, which must not have a checksum yet.
+fn compute_checksum(descriptor: &str) -> Result<String, ChecksumError> {
+    let mut c = 1;
+    let mut cls = 0;
+    let mut clscount = 0;
+    for ch in descriptor.chars() {
+        let pos = INPUT_CHARSET.find(ch).ok_or(ChecksumError::InvalidCharacter(ch))? as u64;
+        // Each character contributes its low 5 bits, groups of three contribute their high bits.
+        c = poly_mod(c, pos & 31);
+        cls = cls * 3 + (pos >> 5);
+        clscount += 1;
+        if clscount == 3 {
+            c = poly_mod(c, cls);
+            cls = 0;
+            clscount = 0;
+        }
+    }
+    if clscount > 0 {
+        c = poly_mod(c, cls);
+    }
+    for _ in 0..CHECKSUM_LENGTH {
+        c = poly_mod(c, 0);
+    }
+    c ^= 1;
+
+    Ok((0..CHECKSUM_LENGTH)
+        .map(|j| CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+        .collect())
+}
+
+/// Feeds `val` into the BCH code over GF(32) used for the checksum.
+fn poly_mod(mut c: u64, val: u64) -> u64 {
+    let c0 = c >> 35;
+    c = ((c & 0x7ffffffff) << 5) ^ val;
+    if c0 & 1 != 0 {
+        c ^= 0xf5dee51989;
+    }
+    if c0 & 2 != 0 {
+        c ^= 0xa9fdca3312;
+    }
+    if c0 & 4 != 0 {
+        c ^= 0x1bab10e32d;
+    }
+    if c0 & 8 != 0 {
+        c ^= 0x3706b1677a;
+    }
+    if c0 & 16 != 0 {
+        c ^= 0x644d626ffd;
+    }
+    c
+}
+
+/// An error verifying or computing a descriptor checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChecksumError {
+    /// The descriptor has no `#checksum` suffix.
+    Missing,
+    /// The checksum is not 8 characters long.
+    InvalidLength(usize),
+    /// The descriptor contains a character that is not allowed in descriptors.
+    InvalidCharacter(char),
+    /// The checksum does not match the descriptor.
+    Mismatch,
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ChecksumError::*;
+
+        match *self {
+            Missing => f.write_str("descriptor has no checksum"),
+            InvalidLength(len) => write!(f, "descriptor checksum has {} characters, not 8", len),
+            InvalidCharacter(ch) => write!(f, "invalid character {:?} in descriptor", ch),
+            Mismatch => f.write_str("descriptor checksum does not match"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChecksumError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ChecksumError::*;
+
+        match *self {
+            Missing | InvalidLength(_) | InvalidCharacter(_) | Mismatch => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From BIP380 and Bitcoin Core's `doc/descriptors.md`.
+    const VALID: &[&str] = &[
+        "raw(deadbeef)#89f8spxm",
+        "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)#8fhd9pwu",
+        "wpkh(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)#8zl0zxma",
+    ];
+
+    #[test]
+    fn verify_known_checksums() {
+        for descriptor in VALID {
+            let body = verify_checksum(descriptor).unwrap();
+            assert_eq!(descriptor.len(), body.len() + 1 + CHECKSUM_LENGTH);
+            assert_eq!(append_checksum(body).unwrap(), *descriptor);
+        }
+    }
+
+    #[test]
+    fn checksum_errors() {
+        assert_eq!(verify_checksum("raw(deadbeef)"), Err(ChecksumError::Missing));
+        assert_eq!(verify_checksum("raw(deadbeef)#89f8spx"), Err(ChecksumError::InvalidLength(7)));
+        assert_eq!(verify_checksum("raw(deadbeef)#89f8spxn"), Err(ChecksumError::Mismatch));
+        assert_eq!(verify_checksum("raw(deadbeee)#89f8spxm"), Err(ChecksumError::Mismatch));
+        assert_eq!(
+            append_checksum("raw(d\u{e9}adbeef)"),
+            Err(ChecksumError::InvalidCharacter('\u{e9}'))
+        );
+    }
+}
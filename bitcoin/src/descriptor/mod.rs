@@ -0,0 +1,382 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Output script descriptors.
+//!
+//! A minimal implementation of [BIP380] output descriptors supporting the `pkh`, `wpkh`, `sh` and
+//! `multi` script expressions, enough to derive the scripts of a watch-only wallet. Keys are either
+//! hex encoded public keys or extended public keys followed by unhardened derivation steps and an
+//! optional trailing `/*` wildcard, key origins (`[fingerprint/path]`) are not supported.
+//!
+//! [BIP380]: <https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki>
+//!
+
+use core::fmt;
+use core::str::FromStr;
+
+use internals::write_err;
+use secp256k1::Secp256k1;
+
+use crate::address::{self, Address};
+use crate::bip32::{self, ChildNumber, Xpub};
+use crate::blockdata::opcodes::all::OP_CHECKMULTISIG;
+use crate::blockdata::script::{Builder, ScriptBuf};
+use crate::crypto::key::{self, PublicKey};
+use crate::network::Network;
+use crate::prelude::*;
+
+/// Descriptor checksums.
+pub mod checksum;
+pub use self::checksum::ChecksumError;
+
+/// The maximum number of keys in a `multi` descriptor.
+const MAX_MULTI_KEYS: usize = 16;
+
+/// A public key in a descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorPublicKey {
+    /// A single public key.
+    Single(PublicKey),
+    /// An extended public key and the unhardened derivation steps to apply to it.
+    Xpub {
+        /// The extended public key.
+        xkey: Xpub,
+        /// The derivation steps following the key.
+        path: Vec<ChildNumber>,
+        /// Whether the path ends with `/*`, i.e. the index is appended when deriving.
+        wildcard: bool,
+    },
+}
+
+impl DescriptorPublicKey {
+    /// Derives the public key at `index`.
+    ///
+    /// The index is only used for wildcard keys, other keys always derive the same public key.
+    pub fn derive_public_key(&self, index: u32) -> Result<PublicKey, DescriptorError> {
+        match *self {
+            DescriptorPublicKey::Single(pk) => Ok(pk),
+            DescriptorPublicKey::Xpub { xkey, ref path, wildcard } => {
+                let mut path = path.clone();
+                if wildcard {
+                    path.push(ChildNumber::from_normal_idx(index).map_err(DescriptorError::Bip32)?);
+                }
+                let secp = Secp256k1::verification_only();
+                let derived = xkey.derive_pub(&secp, &path).map_err(DescriptorError::Bip32)?;
+                Ok(derived.to_pub())
+            }
+        }
+    }
+}
+
+impl fmt::Display for DescriptorPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DescriptorPublicKey::Single(ref pk) => fmt::Display::fmt(pk, f),
+            DescriptorPublicKey::Xpub { ref xkey, ref path, wildcard } => {
+                fmt::Display::fmt(xkey, f)?;
+                for child in path {
+                    write!(f, "/{}", child)?;
+                }
+                if wildcard {
+                    f.write_str("/*")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for DescriptorPublicKey {
+    type Err = DescriptorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('[') {
+            return Err(DescriptorError::KeyOrigin);
+        }
+
+        let mut parts = s.split('/');
+        let key = parts.next().expect("split always yields at least one item");
+        if key.len() == 66 || key.len() == 130 {
+            if s.contains('/') {
+                return Err(DescriptorError::InvalidSyntax);
+            }
+            return key
+                .parse()
+                .map(DescriptorPublicKey::Single)
+                .map_err(DescriptorError::InvalidKey);
+        }
+
+        let xkey = key.parse::<Xpub>().map_err(DescriptorError::Bip32)?;
+        let mut path = Vec::new();
+        let mut wildcard = false;
+        for step in parts {
+            if wildcard {
+                // The wildcard must be the last step.
+                return Err(DescriptorError::InvalidSyntax);
+            }
+            if step == "*" {
+                wildcard = true;
+                continue;
+            }
+            let child = step.parse::<ChildNumber>().map_err(DescriptorError::Bip32)?;
+            if child.is_hardened() {
+                return Err(DescriptorError::Bip32(bip32::Error::CannotDeriveFromHardenedKey));
+            }
+            path.push(child);
+        }
+        Ok(DescriptorPublicKey::Xpub { xkey, path, wildcard })
+    }
+}
+
+/// An output script descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    /// `pkh(KEY)`, pay to public key hash.
+    Pkh(DescriptorPublicKey),
+    /// `wpkh(KEY)`, pay to witness public key hash.
+    Wpkh(DescriptorPublicKey),
+    /// `sh(SCRIPT)`, pay to script hash of the inner descriptor's script.
+    Sh(Box<Descriptor>),
+    /// `multi(k,KEY,...)`, bare `k` of `n` multisig.
+    Multi(usize, Vec<DescriptorPublicKey>),
+}
+
+impl Descriptor {
+    /// Returns the script pubkey of the descriptor with wildcard keys derived at `index`.
+    ///
+    /// # Errors
+    ///
+    /// If `index` is not an unhardened child number, or key derivation fails.
+    pub fn script_pubkey(&self, index: u32) -> Result<ScriptBuf, DescriptorError> {
+        match *self {
+            Descriptor::Pkh(ref key) =>
+                Ok(ScriptBuf::new_p2pkh(&key.derive_public_key(index)?.pubkey_hash())),
+            Descriptor::Wpkh(ref key) => {
+                let pk = key.derive_public_key(index)?;
+                let wpubkey_hash = pk.wpubkey_hash().ok_or(DescriptorError::UncompressedKey)?;
+                Ok(ScriptBuf::new_p2wpkh(&wpubkey_hash))
+            }
+            Descriptor::Sh(ref inner) => Ok(inner.script_pubkey(index)?.to_p2sh()),
+            Descriptor::Multi(threshold, ref keys) => {
+                let mut builder = Builder::new().push_int(threshold as i64);
+                for key in keys {
+                    builder = builder.push_key(&key.derive_public_key(index)?);
+                }
+                Ok(builder.push_int(keys.len() as i64).push_opcode(OP_CHECKMULTISIG).into_script())
+            }
+        }
+    }
+
+    /// Returns the address of the descriptor with wildcard keys derived at `index`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Descriptor::script_pubkey`], and for bare `multi` descriptors (no address form).
+    pub fn address(&self, index: u32, network: Network) -> Result<Address, DescriptorError> {
+        Address::from_script(&self.script_pubkey(index)?, network).map_err(DescriptorError::Address)
+    }
+
+    /// Parses a descriptor expression, without a checksum.
+    fn parse_expression(s: &str, top_level: bool) -> Result<Self, DescriptorError> {
+        let open = s.find('(').ok_or(DescriptorError::InvalidSyntax)?;
+        if !s.ends_with(')') {
+            return Err(DescriptorError::InvalidSyntax);
+        }
+        let (name, args) = (&s[..open], &s[open + 1..s.len() - 1]);
+
+        match name {
+            "pkh" => Ok(Descriptor::Pkh(args.parse()?)),
+            "wpkh" => {
+                let key = args.parse()?;
+                if let DescriptorPublicKey::Single(pk) = key {
+                    if !pk.compressed {
+                        return Err(DescriptorError::UncompressedKey);
+                    }
+                }
+                Ok(Descriptor::Wpkh(key))
+            }
+            "sh" if top_level =>
+                Ok(Descriptor::Sh(Box::new(Descriptor::parse_expression(args, false)?))),
+            "sh" => Err(DescriptorError::InvalidNesting),
+            "multi" => {
+                let mut args = args.split(',');
+                let threshold = args
+                    .next()
+                    .and_then(|k| k.parse::<usize>().ok())
+                    .ok_or(DescriptorError::InvalidMultisig)?;
+                let keys = args.map(str::parse).collect::<Result<Vec<DescriptorPublicKey>, _>>()?;
+                if keys.len() > MAX_MULTI_KEYS || threshold == 0 || threshold > keys.len() {
+                    return Err(DescriptorError::InvalidMultisig);
+                }
+                Ok(Descriptor::Multi(threshold, keys))
+            }
+            _ => Err(DescriptorError::UnknownScript(name.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Descriptor::Pkh(ref key) => write!(f, "pkh({})", key),
+            Descriptor::Wpkh(ref key) => write!(f, "wpkh({})", key),
+            Descriptor::Sh(ref inner) => write!(f, "sh({})", inner),
+            Descriptor::Multi(threshold, ref keys) => {
+                write!(f, "multi({}", threshold)?;
+                for key in keys {
+                    write!(f, ",{}", key)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = DescriptorError;
+
+    /// Parses a descriptor, verifying the `#checksum` suffix if present.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let desc = if s.contains('#') {
+            checksum::verify_checksum(s).map_err(DescriptorError::Checksum)?
+        } else {
+            s
+        };
+        Descriptor::parse_expression(desc, true)
+    }
+}
+
+/// An error parsing or deriving a [`Descriptor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DescriptorError {
+    /// The descriptor is malformed.
+    InvalidSyntax,
+    /// The script expression is not supported.
+    UnknownScript(String),
+    /// A public key is invalid.
+    InvalidKey(key::Error),
+    /// An extended key is invalid or can not be derived.
+    Bip32(bip32::Error),
+    /// Key origins are not supported.
+    KeyOrigin,
+    /// An uncompressed key is used in a segwit descriptor.
+    UncompressedKey,
+    /// The threshold or number of keys of a `multi` descriptor is invalid.
+    InvalidMultisig,
+    /// An `sh` descriptor is nested inside another descriptor.
+    InvalidNesting,
+    /// The checksum is invalid.
+    Checksum(ChecksumError),
+    /// The script has no address form.
+    Address(address::Error),
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DescriptorError::*;
+
+        match *self {
+            InvalidSyntax => f.write_str("invalid descriptor syntax"),
+            UnknownScript(ref name) => write!(f, "unsupported script expression {}", name),
+            InvalidKey(ref e) => write_err!(f, "invalid public key in descriptor"; e),
+            Bip32(ref e) => write_err!(f, "invalid extended key in descriptor"; e),
+            KeyOrigin => f.write_str("key origins are not supported"),
+            UncompressedKey => f.write_str("uncompressed key in segwit descriptor"),
+            InvalidMultisig => f.write_str("invalid multi descriptor threshold or key count"),
+            InvalidNesting => f.write_str("sh descriptor is only allowed at the top level"),
+            Checksum(ref e) => write_err!(f, "invalid descriptor checksum"; e),
+            Address(ref e) => write_err!(f, "descriptor has no address"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DescriptorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DescriptorError::*;
+
+        match *self {
+            InvalidKey(ref e) => Some(e),
+            Bip32(ref e) => Some(e),
+            Address(ref e) => Some(e),
+            Checksum(ref e) => Some(e),
+            InvalidSyntax | UnknownScript(_) | KeyOrigin | UncompressedKey | InvalidMultisig
+            | InvalidNesting => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::test_hex_unwrap as hex;
+
+    use super::*;
+
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    #[test]
+    fn wpkh_xpub_wildcard() {
+        let s = format!("wpkh({}/0/*)", XPUB);
+        let desc = s.parse::<Descriptor>().unwrap();
+        assert_eq!(desc.to_string(), s);
+
+        let spk0 = desc.script_pubkey(0).unwrap();
+        let spk1 = desc.script_pubkey(1).unwrap();
+        assert_eq!(spk0.as_bytes(), &hex!("00140d1c9c02a7be9ba8b8842804feb961481ce6561b")[..]);
+        assert_eq!(spk1.as_bytes(), &hex!("00141a4c3d16409dddc499160230dc84a1182b2ab38e")[..]);
+
+        assert!(format!("{}#wvk84d79", s).parse::<Descriptor>().is_ok());
+        assert_eq!(
+            format!("{}#wvk84d78", s).parse::<Descriptor>(),
+            Err(DescriptorError::Checksum(ChecksumError::Mismatch))
+        );
+        assert!(desc.script_pubkey(1 << 31).is_err());
+    }
+
+    #[test]
+    fn single_key_descriptors() {
+        let key = "02756de182c5dd4b717ea87e693006da62dbb3cddaa4a5cad2ed1f5bbab755f0f5";
+        let pk = key.parse::<PublicKey>().unwrap();
+
+        let pkh = format!("pkh({})", key).parse::<Descriptor>().unwrap();
+        assert_eq!(pkh.script_pubkey(7).unwrap(), ScriptBuf::new_p2pkh(&pk.pubkey_hash()));
+
+        let sh_wpkh = format!("sh(wpkh({}))", key).parse::<Descriptor>().unwrap();
+        let wpkh = ScriptBuf::new_p2wpkh(&pk.wpubkey_hash().unwrap());
+        assert_eq!(sh_wpkh.script_pubkey(0).unwrap(), wpkh.to_p2sh());
+        assert_eq!(sh_wpkh.address(0, Network::Bitcoin).unwrap().script_pubkey(), wpkh.to_p2sh());
+
+        let multi = format!("sh(multi(1,{},{}/0))", key, XPUB).parse::<Descriptor>().unwrap();
+        assert!(multi.script_pubkey(0).unwrap().is_p2sh());
+        let bare = format!("multi(1,{})", key).parse::<Descriptor>().unwrap();
+        assert!(bare.script_pubkey(0).unwrap().is_multisig());
+        assert!(bare.address(0, Network::Bitcoin).is_err());
+    }
+
+    #[test]
+    fn descriptor_errors() {
+        let key = "02756de182c5dd4b717ea87e693006da62dbb3cddaa4a5cad2ed1f5bbab755f0f5";
+        let uncompressed = "048d5141948c1702e8c95f438815794b87f706a8d4cd2bffad1dc1570971032c9b6042a0431ded2478b5c9cf2d81c124a5e57347a3c63ef0e7716cf54d613ba183";
+        let parse = |s: String| s.parse::<Descriptor>();
+
+        assert_eq!(
+            parse(format!("tr({})", key)),
+            Err(DescriptorError::UnknownScript("tr".to_owned()))
+        );
+        assert_eq!(parse(format!("wpkh({}", key)), Err(DescriptorError::InvalidSyntax));
+        assert_eq!(parse(format!("wpkh({})", uncompressed)), Err(DescriptorError::UncompressedKey));
+        assert_eq!(parse(format!("sh(sh(pkh({})))", key)), Err(DescriptorError::InvalidNesting));
+        assert_eq!(parse(format!("multi(2,{})", key)), Err(DescriptorError::InvalidMultisig));
+        assert_eq!(parse(format!("multi(0,{})", key)), Err(DescriptorError::InvalidMultisig));
+        assert_eq!(
+            parse(format!("pkh([d34db33f/44'/0'/0']{})", key)),
+            Err(DescriptorError::KeyOrigin)
+        );
+        assert_eq!(
+            parse(format!("pkh({}/0'/*)", XPUB)),
+            Err(DescriptorError::Bip32(bip32::Error::CannotDeriveFromHardenedKey))
+        );
+        assert_eq!(parse(format!("pkh({}/*/0)", XPUB)), Err(DescriptorError::InvalidSyntax));
+        assert!(matches!(parse("pkh(02abc)".to_owned()), Err(DescriptorError::Bip32(_))));
+    }
+}
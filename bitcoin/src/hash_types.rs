@@ -50,6 +50,23 @@ pub(crate) use impl_asref_push_bytes;
 // newtypes module is solely here so we can rustfmt::skip.
 pub use newtypes::*;
 
+/// Computes the HASH160 (RIPEMD160 of SHA256) of `data`, as committed to by P2PKH and P2SH
+/// outputs.
+///
+/// Use [`PubkeyHash`](crate::PubkeyHash) or [`ScriptHash`](crate::ScriptHash) for a typed hash.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    use hashes::Hash;
+
+    hashes::hash160::Hash::hash(data).to_byte_array()
+}
+
+/// Computes the double SHA256 of `data`, as used for transaction ids and block hashes.
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    use hashes::Hash;
+
+    hashes::sha256d::Hash::hash(data).to_byte_array()
+}
+
 #[rustfmt::skip]
 mod newtypes {
     use hashes::{sha256d, hash_newtype};
@@ -82,6 +99,13 @@ mod newtypes {
         pub struct FilterHeader(sha256d::Hash);
     }
 
+    impl BlockHash {
+        /// Creates a block hash from its bytes in `const` context.
+        pub(crate) const fn from_byte_array_const(bytes: [u8; 32]) -> Self {
+            BlockHash(sha256d::Hash(bytes))
+        }
+    }
+
     impl_hashencode!(Txid);
     impl_hashencode!(Wtxid);
     impl_hashencode!(BlockHash);
@@ -92,3 +116,36 @@ mod newtypes {
     impl_hashencode!(FilterHash);
     impl_hashencode!(FilterHeader);
 }
+
+#[cfg(test)]
+mod tests {
+    use hashes::Hash;
+
+    use super::*;
+    use crate::address::{Address, Payload};
+    use crate::blockdata::constants::genesis_block;
+    use crate::consensus::encode::serialize;
+    use crate::crypto::key::PublicKey;
+    use crate::network::Network;
+
+    #[test]
+    fn hash160_of_pubkey() {
+        let pk = "032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af"
+            .parse::<PublicKey>()
+            .unwrap();
+        let address = Address::p2pkh(&pk, Network::Bitcoin);
+        match address.payload() {
+            Payload::PubkeyHash(hash) => assert_eq!(hash160(&pk.to_bytes()), hash.to_byte_array()),
+            _ => panic!("not a P2PKH address"),
+        }
+    }
+
+    #[test]
+    fn sha256d_of_header() {
+        let genesis = genesis_block(Network::Bitcoin);
+        let header = serialize(&genesis.header);
+        assert_eq!(sha256d(&header), genesis.block_hash().to_byte_array());
+        let coinbase = &genesis.txdata[0];
+        assert_eq!(sha256d(&serialize(coinbase)), coinbase.txid().to_byte_array());
+    }
+}
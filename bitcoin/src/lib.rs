@@ -102,12 +102,16 @@ pub mod bip152;
 pub mod bip158;
 pub mod bip32;
 pub mod blockdata;
+#[cfg(feature = "std")]
+pub mod chain;
 pub mod consensus;
+pub mod descriptor;
 // Private until we either make this a crate or flatten it - still to be decided.
 pub(crate) mod crypto;
 pub mod error;
 pub mod hash_types;
 pub mod merkle_tree;
+pub mod mining;
 pub mod network;
 pub mod policy;
 pub mod pow;
@@ -115,6 +119,8 @@ pub mod psbt;
 pub mod sign_message;
 pub mod string;
 pub mod taproot;
+#[cfg(feature = "std")]
+pub mod utxo;
 
 // May depend on crate features and we don't want to bother with it
 #[allow(unused)]
@@ -133,7 +139,13 @@ pub use crate::address::{Address, AddressType};
 pub use crate::amount::{Amount, Denomination, SignedAmount};
 pub use crate::bip32::XKeyIdentifier;
 pub use crate::blockdata::block::{self, Block};
-pub use crate::blockdata::constants;
+pub use crate::blockdata::constants::{
+    self, ChainHash, COINBASE_MATURITY, DIFFCHANGE_INTERVAL, DIFFCHANGE_TIMESPAN, MAX_MONEY,
+    MAX_SCRIPT_ELEMENT_SIZE, PUBKEY_ADDRESS_PREFIX_MAIN, PUBKEY_ADDRESS_PREFIX_REGTEST,
+    PUBKEY_ADDRESS_PREFIX_TEST, SCRIPT_ADDRESS_PREFIX_MAIN, SCRIPT_ADDRESS_PREFIX_REGTEST,
+    SCRIPT_ADDRESS_PREFIX_TEST, SUBSIDY_HALVING_INTERVAL, TARGET_BLOCK_SPACING,
+    WITNESS_SCALE_FACTOR,
+};
 pub use crate::blockdata::fee_rate::FeeRate;
 pub use crate::blockdata::locktime::{self, absolute, relative};
 pub use crate::blockdata::opcodes::{self, Opcode};
@@ -154,6 +166,7 @@ pub use crate::hash_types::{
 };
 pub use crate::merkle_tree::MerkleBlock;
 pub use crate::network::Network;
+pub use crate::p2p::Magic;
 pub use crate::pow::{CompactTarget, Target, Work};
 pub use crate::psbt::Psbt;
 pub use crate::sighash::{EcdsaSighashType, TapSighashType};
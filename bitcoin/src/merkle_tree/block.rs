@@ -240,6 +240,7 @@ impl PartialMerkleTree {
     /// let tree = PartialMerkleTree::from_txids(&txids, &matches);
     /// assert!(tree.extract_matches(&mut vec![], &mut vec![]).is_ok());
     /// ```
+    #[doc(alias = "new")]
     pub fn from_txids(txids: &[Txid], matches: &[bool]) -> Self {
         // We can never have zero txs in a merkle block, we always need the coinbase tx
         assert_ne!(txids.len(), 0);
@@ -733,6 +734,27 @@ mod tests {
         assert_eq!(index[1], 8);
     }
 
+    #[test]
+    fn pmt_single_match() {
+        use hashes::Hash;
+
+        use crate::hash_types::TxMerkleNode;
+
+        let txids: Vec<Txid> = (0u8..4).map(|i| Txid::hash(&[i])).collect();
+        let tree = PartialMerkleTree::from_txids(&txids, &[false, true, false, false]);
+        // The sibling leaf, the matched leaf and the hash of the right subtree.
+        assert_eq!(tree.hashes().len(), 3);
+        assert_eq!(*tree.bits(), vec![true, true, false, true, false]);
+
+        let mut matches = vec![];
+        let mut indexes = vec![];
+        let root = tree.extract_matches(&mut matches, &mut indexes).unwrap();
+        let leaves = txids.iter().map(|txid| TxMerkleNode::from_raw_hash(txid.to_raw_hash()));
+        assert_eq!(Some(root), crate::merkle_tree::calculate_root(leaves));
+        assert_eq!(matches, vec![txids[1]]);
+        assert_eq!(indexes, vec![1]);
+    }
+
     /// Create a CMerkleBlock using a list of txids which will not be found in the given block
     #[test]
     fn merkleblock_construct_from_txids_not_found() {
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Block templates for mining.
+//!
+//! This module provides [`BlockTemplate`], the response to a BIP-22 `getblocktemplate` request,
+//! serialized (with the `serde` feature) to the JSON format expected by mining software.
+//!
+
+use crate::amount::Amount;
+use crate::blockdata::block;
+use crate::blockdata::constants::MAX_BLOCK_SIGOPS_COST;
+use crate::blockdata::transaction::Transaction;
+use crate::blockdata::weight::Weight;
+use crate::consensus::encode;
+use crate::hash_types::{BlockHash, Txid};
+use crate::network::Network;
+use crate::pow::CompactTarget;
+use crate::prelude::*;
+
+/// The parts of a template miners may change, see BIP-23.
+const MUTABLE: &[&str] = &["time", "transactions", "prevblock"];
+
+/// The range of nonces miners may use, as serialized in the template.
+const NONCE_RANGE: &str = "00000000ffffffff";
+
+/// The maximum size of a serialized block, witness data included.
+const MAX_BLOCK_SERIALIZED_SIZE: u32 = 4_000_000;
+
+/// A block template, as returned by `getblocktemplate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTemplate {
+    /// The version of the block to mine.
+    pub version: block::Version,
+    /// The hash of the block the template builds on.
+    pub prev_block_hash: BlockHash,
+    /// The transactions to include after the coinbase.
+    pub transactions: Vec<TemplateTransaction>,
+    /// Data to include in the coinbase scriptSig.
+    pub coinbase_aux: CoinbaseAux,
+    /// The maximum value of the coinbase outputs, i.e. the subsidy plus the fees.
+    pub coinbase_value: Amount,
+    /// The target the block hash must meet.
+    pub target: CompactTarget,
+    /// The minimum time of the block.
+    pub min_time: u32,
+    /// The current time, to use as the block time.
+    pub cur_time: u32,
+    /// The ways miners may alter the template.
+    pub mutable: Vec<String>,
+    /// The height of the block to mine, to be committed to in the coinbase (BIP-34).
+    pub height: u32,
+    /// The soft fork deployments the template relies on, see BIP-9.
+    pub rules: Vec<String>,
+    /// The maximum sigop cost of the block.
+    pub sigop_limit: u32,
+    /// The maximum size in bytes of the serialized block.
+    pub size_limit: u32,
+    /// The maximum weight of the block.
+    pub weight_limit: Weight,
+    /// Whether transaction data is serialized without witnesses, for miners not supporting segwit.
+    pub non_witness_data: bool,
+}
+
+impl BlockTemplate {
+    /// Creates a template for the block following `tip` at `height` on `network`, including `txs`
+    /// in order.
+    ///
+    /// `fees` are the total fees paid by `txs` and `subsidy` is the block subsidy at `height`.
+    /// The subsidy is not derived from `network` because the Lebowkis emission schedule is not
+    /// part of this crate: [`SUBSIDY_HALVING_INTERVAL`] is inherited from Bitcoin and the genesis
+    /// coinbase does not pay a Bitcoin-like subsidy.
+    ///
+    /// The target is the one of `tip`, callers must set [`BlockTemplate::target`] for blocks at a
+    /// difficulty adjustment. The minimum time is one second after `tip`, which is never before
+    /// the median time past. The crate has no clock, so the current time is also set to the
+    /// minimum time and callers should update [`BlockTemplate::cur_time`].
+    ///
+    /// [`SUBSIDY_HALVING_INTERVAL`]: crate::blockdata::constants::SUBSIDY_HALVING_INTERVAL
+    pub fn new(
+        tip: &block::Header,
+        txs: &[Transaction],
+        fees: Amount,
+        subsidy: Amount,
+        height: u32,
+        network: Network,
+    ) -> Self {
+        let mut txids: Vec<Txid> = Vec::with_capacity(txs.len());
+        let transactions = txs
+            .iter()
+            .map(|tx| {
+                let mut depends: Vec<usize> = tx
+                    .input
+                    .iter()
+                    .filter_map(|input| {
+                        txids.iter().position(|txid| *txid == input.previous_output.txid)
+                    })
+                    .map(|index| index + 1)
+                    .collect();
+                depends.sort_unstable();
+                depends.dedup();
+                txids.push(tx.txid());
+                TemplateTransaction { transaction: tx.clone(), depends }
+            })
+            .collect();
+
+        let mut rules = vec!["csv".to_string(), "segwit".to_string()];
+        if network == Network::Signet {
+            // Miners must know how to sign signet blocks, see BIP-325.
+            rules.push("!signet".to_string());
+        }
+        let min_time = tip.time.saturating_add(1);
+
+        BlockTemplate {
+            version: block::Version::TWO,
+            prev_block_hash: tip.block_hash(),
+            transactions,
+            coinbase_aux: CoinbaseAux::default(),
+            coinbase_value: subsidy + fees,
+            target: tip.bits,
+            min_time,
+            cur_time: min_time,
+            mutable: MUTABLE.iter().map(|s| s.to_string()).collect(),
+            height,
+            rules,
+            sigop_limit: MAX_BLOCK_SIGOPS_COST as u32,
+            size_limit: MAX_BLOCK_SERIALIZED_SIZE,
+            weight_limit: Weight::MAX_BLOCK,
+            non_witness_data: false,
+        }
+    }
+}
+
+/// A transaction of a [`BlockTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateTransaction {
+    /// The transaction.
+    pub transaction: Transaction,
+    /// The 1-based indices in the template of the transactions this one spends from.
+    pub depends: Vec<usize>,
+}
+
+impl TemplateTransaction {
+    /// Returns the transaction serialized as it goes in the block, with or without witnesses.
+    fn data(&self, non_witness_data: bool) -> Vec<u8> {
+        if non_witness_data {
+            let mut tx = self.transaction.clone();
+            for input in &mut tx.input {
+                input.witness.clear();
+            }
+            encode::serialize(&tx)
+        } else {
+            encode::serialize(&self.transaction)
+        }
+    }
+}
+
+/// Data miners must include in the coinbase scriptSig.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoinbaseAux {
+    /// Flags, serialized as hex.
+    pub flags: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::ser::{SerializeStruct, Serializer};
+    use serde::Serialize;
+
+    use super::*;
+    use crate::pow::Target;
+
+    impl Serialize for BlockTemplate {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let transactions: Vec<_> = self
+                .transactions
+                .iter()
+                .map(|tx| SerializeTransaction { tx, non_witness_data: self.non_witness_data })
+                .collect();
+            let target = Target::from_compact(self.target).to_be_bytes();
+
+            let mut st = serializer.serialize_struct("BlockTemplate", 16)?;
+            st.serialize_field("version", &self.version.to_consensus())?;
+            st.serialize_field("rules", &self.rules)?;
+            st.serialize_field("previousblockhash", &self.prev_block_hash.to_string())?;
+            st.serialize_field("transactions", &transactions)?;
+            st.serialize_field("coinbaseaux", &self.coinbase_aux)?;
+            st.serialize_field("coinbasevalue", &self.coinbase_value.to_sat())?;
+            st.serialize_field("target", &target.to_lower_hex_string())?;
+            st.serialize_field("bits", &format!("{:08x}", self.target.to_consensus()))?;
+            st.serialize_field("mintime", &self.min_time)?;
+            st.serialize_field("mutable", &self.mutable)?;
+            st.serialize_field("noncerange", NONCE_RANGE)?;
+            st.serialize_field("sigoplimit", &self.sigop_limit)?;
+            st.serialize_field("sizelimit", &self.size_limit)?;
+            st.serialize_field("weightlimit", &self.weight_limit.to_wu())?;
+            st.serialize_field("curtime", &self.cur_time)?;
+            st.serialize_field("height", &self.height)?;
+            st.end()
+        }
+    }
+
+    struct SerializeTransaction<'a> {
+        tx: &'a TemplateTransaction,
+        non_witness_data: bool,
+    }
+
+    impl<'a> Serialize for SerializeTransaction<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let tx = &self.tx.transaction;
+
+            let mut st = serializer.serialize_struct("TemplateTransaction", 5)?;
+            st.serialize_field("data", &self.tx.data(self.non_witness_data).to_lower_hex_string())?;
+            st.serialize_field("txid", &tx.txid().to_string())?;
+            st.serialize_field("hash", &tx.wtxid().to_string())?;
+            st.serialize_field("depends", &self.tx.depends)?;
+            st.serialize_field("weight", &tx.weight().to_wu())?;
+            st.end()
+        }
+    }
+
+    impl Serialize for CoinbaseAux {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut st = serializer.serialize_struct("CoinbaseAux", 1)?;
+            st.serialize_field("flags", &self.flags.to_lower_hex_string())?;
+            st.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashes::Hash;
+    use hex::test_hex_unwrap as hex;
+
+    use super::*;
+    use crate::blockdata::constants::genesis_block;
+    use crate::blockdata::locktime::absolute;
+    use crate::blockdata::script::ScriptBuf;
+    use crate::blockdata::transaction::{self, OutPoint, TxIn, TxOut};
+    use crate::blockdata::witness::Witness;
+
+    fn spend(previous_output: OutPoint) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                witness: Witness::from_slice(&[vec![0; 72]]),
+                ..Default::default()
+            }],
+            output: vec![TxOut { value: Amount::from_sat(1_000), script_pubkey: ScriptBuf::new() }],
+        }
+    }
+
+    fn template() -> BlockTemplate {
+        let tip = genesis_block(Network::Bitcoin).header;
+        let first = spend(OutPoint::new(Txid::from_byte_array([1; 32]), 0));
+        let second = spend(OutPoint::new(first.txid(), 0));
+        let fees = Amount::from_sat(2_000);
+        let subsidy = Amount::from_sat(1_000_000);
+        BlockTemplate::new(&tip, &[first, second], fees, subsidy, 1, Network::Bitcoin)
+    }
+
+    #[test]
+    fn new_block_template() {
+        let tip = genesis_block(Network::Bitcoin).header;
+        let template = template();
+
+        assert_eq!(template.prev_block_hash, tip.block_hash());
+        assert_eq!(template.coinbase_value, Amount::from_sat(1_002_000));
+        assert_eq!(template.target, tip.bits);
+        assert_eq!(template.min_time, tip.time + 1);
+        assert_eq!(template.cur_time, template.min_time);
+        assert_eq!(template.height, 1);
+        assert_eq!(template.rules, vec!["csv", "segwit"]);
+        assert_eq!(template.sigop_limit, 80_000);
+        assert_eq!(template.size_limit, 4_000_000);
+        assert_eq!(template.weight_limit, Weight::MAX_BLOCK);
+        assert_eq!(template.transactions.len(), 2);
+        assert!(template.transactions[0].depends.is_empty());
+        assert_eq!(template.transactions[1].depends, vec![1]);
+
+        let signet = BlockTemplate::new(&tip, &[], Amount::ZERO, Amount::ZERO, 1, Network::Signet);
+        assert_eq!(signet.rules, vec!["csv", "segwit", "!signet"]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn block_template_json() {
+        let mut template = template();
+        let json = serde_json::to_value(&template).unwrap();
+
+        let object = json.as_object().unwrap();
+        let mut keys: Vec<_> = object.keys().map(|s| s.as_str()).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "bits",
+                "coinbaseaux",
+                "coinbasevalue",
+                "curtime",
+                "height",
+                "mintime",
+                "mutable",
+                "noncerange",
+                "previousblockhash",
+                "rules",
+                "sigoplimit",
+                "sizelimit",
+                "target",
+                "transactions",
+                "version",
+                "weightlimit"
+            ]
+        );
+        assert_eq!(json["version"], 2);
+        assert_eq!(json["previousblockhash"], template.prev_block_hash.to_string());
+        assert_eq!(json["coinbaseaux"]["flags"], "");
+        assert_eq!(json["coinbasevalue"], 1_002_000);
+        assert_eq!(json["bits"], format!("{:08x}", template.target.to_consensus()));
+        assert_eq!(json["target"].as_str().unwrap().len(), 64);
+        assert_eq!(json["mutable"], serde_json::json!(["time", "transactions", "prevblock"]));
+        assert_eq!(json["rules"], serde_json::json!(["csv", "segwit"]));
+        assert_eq!(json["noncerange"], "00000000ffffffff");
+        assert_eq!(json["sigoplimit"], 80_000);
+        assert_eq!(json["sizelimit"], 4_000_000);
+        assert_eq!(json["weightlimit"], 4_000_000);
+        assert_eq!(json["curtime"], template.cur_time);
+
+        let tx = &template.transactions[1];
+        let json_tx = &json["transactions"][1];
+        assert_eq!(json_tx["data"], encode::serialize_hex(&tx.transaction));
+        assert_eq!(json_tx["txid"], tx.transaction.txid().to_string());
+        assert_eq!(json_tx["hash"], tx.transaction.wtxid().to_string());
+        assert_eq!(json_tx["depends"], serde_json::json!([1]));
+        assert_eq!(json_tx["weight"], tx.transaction.weight().to_wu());
+
+        template.non_witness_data = true;
+        let json = serde_json::to_value(&template).unwrap();
+        let data = json["transactions"][1]["data"].as_str().unwrap();
+        let tx: Transaction = encode::deserialize(&hex!(data)).unwrap();
+        assert!(tx.input[0].witness.is_empty());
+        assert_eq!(tx.txid(), template.transactions[1].transaction.txid());
+    }
+}
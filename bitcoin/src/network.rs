@@ -27,8 +27,15 @@ use internals::write_err;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::constants::ChainHash;
+use crate::address::AddressType;
+use crate::bip32::{
+    VERSION_BYTES_MAINNET_PRIVATE, VERSION_BYTES_MAINNET_PUBLIC, VERSION_BYTES_TESTNETS_PRIVATE,
+    VERSION_BYTES_TESTNETS_PUBLIC,
+};
+use crate::constants::{genesis_block, genesis_block_hash, ChainHash, COINBASE_MATURITY};
+use crate::hash_types::{BlockHash, Txid};
 use crate::p2p::Magic;
+use crate::pow::CompactTarget;
 use crate::prelude::{String, ToOwned};
 
 /// The cryptocurrency network to act on.
@@ -48,7 +55,19 @@ pub enum Network {
     Regtest,
 }
 
+/// Every network variant, in declaration order.
+static ALL_NETWORKS: [Network; 4] =
+    [Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest];
+
 impl Network {
+    /// Returns all networks, in declaration order.
+    pub fn all() -> [Network; 4] { ALL_NETWORKS }
+
+    /// Returns an iterator over all networks, in declaration order.
+    ///
+    /// `Network` is `#[non_exhaustive]`, networks added in the future will be included.
+    pub fn iter() -> impl Iterator<Item = Network> { ALL_NETWORKS.iter().copied() }
+
     /// Creates a `Network` from the magic bytes.
     ///
     /// # Examples
@@ -77,6 +96,12 @@ impl Network {
     /// ```
     pub fn magic(self) -> Magic { Magic::from(self) }
 
+    /// Returns true if `self` and `other` use the same network magic bytes.
+    ///
+    /// Note that Lebowkis testnet and signet share their magic bytes, so peers on these two
+    /// networks can not be told apart by the message header alone.
+    pub fn is_same_magic(self, other: Network) -> bool { self.magic() == other.magic() }
+
     /// Converts a `Network` to its equivalent `bitcoind -chain` argument name.
     ///
     /// ```bash
@@ -144,6 +169,124 @@ impl Network {
     pub fn from_chain_hash(chain_hash: ChainHash) -> Option<Network> {
         Network::try_from(chain_hash).ok()
     }
+
+    /// Returns the network whose genesis block hash is `hash`, if any.
+    ///
+    /// This compares against [`genesis_block_hash`] so no genesis block is constructed. All
+    /// Lebowkis networks currently share the same genesis block, for which [`Network::Bitcoin`]
+    /// is returned: use the network magic (see [`Network::from_magic`]) to tell them apart.
+    ///
+    /// [`genesis_block_hash`]: crate::blockdata::constants::genesis_block_hash
+    pub fn from_genesis_hash(hash: &BlockHash) -> Option<Network> {
+        Network::iter().find(|network| genesis_block_hash(*network) == *hash)
+    }
+
+    /// Returns the address type wallets should use for new addresses on this network.
+    ///
+    /// [`Params`] records no SegWit activation on any Lebowkis network, so legacy P2PKH is
+    /// preferred everywhere. SegWit addresses, when requested explicitly, use
+    /// [`DEFAULT_SEGWIT_VERSION`].
+    ///
+    /// [`Params`]: crate::consensus::Params
+    /// [`DEFAULT_SEGWIT_VERSION`]: crate::blockdata::constants::DEFAULT_SEGWIT_VERSION
+    pub fn preferred_address_type(self) -> AddressType {
+        match self {
+            Network::Bitcoin | Network::Testnet | Network::Signet | Network::Regtest =>
+                AddressType::P2pkh,
+        }
+    }
+
+    /// Returns the number of blocks after which a coinbase output can be spent on this network.
+    ///
+    /// All Lebowkis networks, including regtest, use [`COINBASE_MATURITY`].
+    pub fn coinbase_maturity(self) -> u32 {
+        match self {
+            Network::Bitcoin | Network::Testnet | Network::Signet | Network::Regtest =>
+                COINBASE_MATURITY,
+        }
+    }
+
+    /// Returns the BIP32 version bytes of extended public keys on this network.
+    ///
+    /// Mainnet keys use the BIP32 mainnet version and are encoded with an `xpub` prefix, keys of
+    /// the testnet networks share a version encoded with a `dgub` prefix.
+    pub fn xpub_version(self) -> u32 {
+        match self {
+            Network::Bitcoin => u32::from_be_bytes(VERSION_BYTES_MAINNET_PUBLIC),
+            Network::Testnet | Network::Signet | Network::Regtest =>
+                u32::from_be_bytes(VERSION_BYTES_TESTNETS_PUBLIC),
+        }
+    }
+
+    /// Returns the BIP32 version bytes of extended private keys on this network.
+    ///
+    /// Mainnet keys are encoded with an `xprv` prefix, keys of the testnet networks with a `dgpv`
+    /// prefix.
+    pub fn xprv_version(self) -> u32 {
+        match self {
+            Network::Bitcoin => u32::from_be_bytes(VERSION_BYTES_MAINNET_PRIVATE),
+            Network::Testnet | Network::Signet | Network::Regtest =>
+                u32::from_be_bytes(VERSION_BYTES_TESTNETS_PRIVATE),
+        }
+    }
+
+    /// Returns the network using `version` for extended public keys.
+    ///
+    /// Testnet, signet and regtest share their version bytes, these are returned as
+    /// [`Network::Testnet`].
+    pub fn from_xpub_version(version: u32) -> Option<Network> {
+        let version = version.to_be_bytes();
+        if version == VERSION_BYTES_MAINNET_PUBLIC {
+            Some(Network::Bitcoin)
+        } else if version == VERSION_BYTES_TESTNETS_PUBLIC {
+            Some(Network::Testnet)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the identifying parameters of the genesis block of this network.
+    ///
+    /// Useful to log on startup, or to compare against a peer, to check the right network is used.
+    pub fn genesis_info(self) -> GenesisInfo {
+        let block = genesis_block(self);
+        GenesisInfo {
+            block_hash: block.block_hash(),
+            coinbase_txid: block.txdata[0].txid(),
+            timestamp: block.header.time,
+            bits: block.header.bits,
+            nonce: block.header.nonce,
+        }
+    }
+}
+
+/// The identifying parameters of a network's genesis block, see [`Network::genesis_info`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GenesisInfo {
+    /// The hash of the genesis block.
+    pub block_hash: BlockHash,
+    /// The txid of the genesis coinbase transaction.
+    pub coinbase_txid: Txid,
+    /// The timestamp of the genesis block.
+    pub timestamp: u32,
+    /// The target of the genesis block.
+    pub bits: CompactTarget,
+    /// The nonce of the genesis block.
+    pub nonce: u32,
+}
+
+impl fmt::Display for GenesisInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "genesis block {} (coinbase {}, time {}, bits {:#010x}, nonce {})",
+            self.block_hash,
+            self.coinbase_txid,
+            self.timestamp,
+            self.bits.to_consensus(),
+            self.nonce
+        )
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -290,6 +433,126 @@ mod tests {
         assert_eq!(deserialize(&[0xc0, 0xc0, 0xc0, 0xc0]).ok(), Some(Network::Regtest.magic()));
     }
 
+    #[test]
+    fn genesis_info() {
+        use crate::blockdata::constants::genesis_block_hash;
+        use crate::pow::CompactTarget;
+
+        let info = Network::Bitcoin.genesis_info();
+        assert_eq!(info.block_hash, genesis_block_hash(Network::Bitcoin));
+        assert_eq!(
+            info.coinbase_txid.to_string(),
+            "a3672b7d42fe5cbb293f7924e4f6d4890a466ccf18e9327a12aeac1ef5d1590f"
+        );
+        assert_eq!(info.timestamp, 1374378315);
+        assert_eq!(info.nonce, 1369296945);
+        assert_eq!(info.bits, CompactTarget::from_consensus(0x1e0ffff0));
+        assert_eq!(
+            info.to_string(),
+            "genesis block d14b0d413fcd854d25ca9382888acad7e688995422b6cbcb38dec8ee006b7130 \
+             (coinbase a3672b7d42fe5cbb293f7924e4f6d4890a466ccf18e9327a12aeac1ef5d1590f, \
+             time 1374378315, bits 0x1e0ffff0, nonce 1369296945)"
+        );
+    }
+
+    #[test]
+    fn from_genesis_hash() {
+        use hashes::Hash;
+
+        use crate::blockdata::constants::genesis_block;
+        use crate::hash_types::BlockHash;
+
+        let networks = [Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest];
+        for network in networks.iter() {
+            let hash = genesis_block(*network).block_hash();
+            // The networks share a genesis block, the first match wins.
+            assert_eq!(Network::from_genesis_hash(&hash), Some(Network::Bitcoin));
+        }
+        assert_eq!(Network::from_genesis_hash(&BlockHash::all_zeros()), None);
+    }
+
+    #[test]
+    fn preferred_address_type() {
+        use crate::address::AddressType;
+        use crate::blockdata::constants::DEFAULT_SEGWIT_VERSION;
+        use crate::blockdata::script::witness_version::WitnessVersion;
+
+        let preferred = Network::Bitcoin.preferred_address_type();
+        for network in [Network::Testnet, Network::Signet, Network::Regtest].iter() {
+            assert_eq!(network.preferred_address_type(), preferred);
+        }
+        assert_eq!(preferred, AddressType::P2pkh);
+        assert_ne!(preferred, AddressType::P2tr);
+        assert_eq!(DEFAULT_SEGWIT_VERSION, WitnessVersion::V0);
+    }
+
+    #[test]
+    fn iter() {
+        assert_eq!(Network::iter().count(), 4);
+        assert!(Network::iter().eq(Network::all().iter().copied()));
+        let networks = Network::all();
+        for (i, a) in networks.iter().enumerate() {
+            for b in &networks[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_eq!(
+            Network::all(),
+            [Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest]
+        );
+    }
+
+    #[test]
+    fn xpub_xprv_version() {
+        use secp256k1::Secp256k1;
+
+        use crate::bip32::{Xpriv, Xpub};
+
+        let secp = Secp256k1::new();
+        for network in Network::iter() {
+            let (xpub_prefix, xprv_prefix) =
+                if network == Network::Bitcoin { ("xpub", "xprv") } else { ("dgub", "dgpv") };
+
+            let xpriv = Xpriv::new_master(network, &[0x42; 32]).unwrap();
+            let xpub = Xpub::from_priv(&secp, &xpriv);
+            assert_eq!(xpub.encode()[..4], network.xpub_version().to_be_bytes());
+            assert_eq!(xpriv.encode()[..4], network.xprv_version().to_be_bytes());
+            assert!(xpub.to_string().starts_with(xpub_prefix));
+            assert!(xpriv.to_string().starts_with(xprv_prefix));
+
+            let parsed = Network::from_xpub_version(network.xpub_version()).unwrap();
+            assert!(parsed == network || parsed == Network::Testnet);
+        }
+        assert_eq!(Network::Bitcoin.xpub_version(), 0x0488_b21e);
+        assert_eq!(Network::Regtest.xprv_version(), 0x02fa_c398);
+
+        // Bitcoin's testnet `tpub` version and private key versions are not extended public key
+        // versions of any network.
+        assert_eq!(Network::from_xpub_version(0x0435_87cf), None);
+        assert_eq!(Network::from_xpub_version(Network::Bitcoin.xprv_version()), None);
+    }
+
+    #[test]
+    fn is_same_magic() {
+        use super::Magic;
+
+        let bitcoin_mainnet = Magic::from_bytes([0xf9, 0xbe, 0xb4, 0xd9]);
+        let networks = Network::all();
+        for (i, a) in networks.iter().enumerate() {
+            assert!(a.is_same_magic(*a));
+            assert_ne!(a.magic(), bitcoin_mainnet);
+            for b in &networks[i + 1..] {
+                // Testnet and signet are the only networks sharing magic bytes.
+                let shared = matches!(
+                    (a, b),
+                    (Network::Testnet, Network::Signet) | (Network::Signet, Network::Testnet)
+                );
+                assert_eq!(a.is_same_magic(*b), shared, "{} and {}", a, b);
+                assert_eq!(b.is_same_magic(*a), shared, "{} and {}", b, a);
+            }
+        }
+    }
+
     #[test]
     fn string_test() {
         assert_eq!(Network::Bitcoin.to_string(), "bitcoin");
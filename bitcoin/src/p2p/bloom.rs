@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! BIP37 bloom filters.
+//!
+//! This module provides [`BloomFilter`], the filter a lightweight client loads into its peers
+//! with a [`FilterLoad`] message so that they only relay the transactions matching it.
+//!
+
+use core::convert::TryFrom;
+use core::f64::consts::LN_2;
+use core::{cmp, fmt};
+
+use crate::p2p::message_bloom::{BloomFlags, FilterLoad};
+use crate::prelude::*;
+
+/// The maximum size of a filter in bytes.
+pub const MAX_BLOOM_FILTER_SIZE: usize = 36_000;
+
+/// The maximum number of hash functions of a filter.
+pub const MAX_HASH_FUNCS: u32 = 50;
+
+/// Multiplier of the hash function number in the seed of each hash function.
+const SEED_MULTIPLIER: u32 = 0xFBA4C795;
+
+/// A BIP37 bloom filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    data: Vec<u8>,
+    hash_funcs: u32,
+    tweak: u32,
+    flags: BloomFlags,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter for `elements` elements with a false positive rate of `fp_rate`.
+    ///
+    /// The size and number of hash functions are computed as in BIP37, so are capped at
+    /// [`MAX_BLOOM_FILTER_SIZE`] and [`MAX_HASH_FUNCS`]. The tweak and flags are zero, use
+    /// [`BloomFilter::from_bytes`] to choose them.
+    pub fn new(elements: usize, fp_rate: f64) -> BloomFilter {
+        let elements = cmp::max(elements, 1);
+        let bits = -1.0 / (LN_2 * LN_2) * elements as f64 * fp_rate.ln();
+        let size = cmp::min(bits as usize, MAX_BLOOM_FILTER_SIZE * 8) / 8;
+        // BIP37 divides the number of bits by the number of elements as integers.
+        let hash_funcs = cmp::min(((size * 8 / elements) as f64 * LN_2) as u32, MAX_HASH_FUNCS);
+
+        BloomFilter { data: vec![0; size], hash_funcs, tweak: 0, flags: BloomFlags::None }
+    }
+
+    /// Creates a filter from its serialized parts, as in a [`FilterLoad`] message.
+    ///
+    /// The parts are not checked against [`MAX_BLOOM_FILTER_SIZE`] and [`MAX_HASH_FUNCS`], use
+    /// `BloomFilter::try_from` on a [`FilterLoad`] to check them.
+    pub fn from_bytes(bytes: &[u8], hash_funcs: u8, tweak: u32, flags: BloomFlags) -> BloomFilter {
+        BloomFilter { data: bytes.to_vec(), hash_funcs: hash_funcs.into(), tweak, flags }
+    }
+
+    /// Adds `data` to the filter.
+    pub fn insert(&mut self, data: &[u8]) {
+        if self.data.is_empty() {
+            return;
+        }
+        for n in 0..self.hash_funcs {
+            let index = self.hash(n, data);
+            self.data[index >> 3] |= 1 << (7 & index);
+        }
+    }
+
+    /// Returns `true` if `data` may have been added to the filter.
+    ///
+    /// An empty filter matches everything.
+    pub fn contains(&self, data: &[u8]) -> bool {
+        if self.data.is_empty() {
+            return true;
+        }
+        (0..self.hash_funcs).all(|n| {
+            let index = self.hash(n, data);
+            self.data[index >> 3] & (1 << (7 & index)) != 0
+        })
+    }
+
+    /// Returns the bytes of the filter.
+    pub fn as_bytes(&self) -> &[u8] { &self.data }
+
+    /// Returns the number of hash functions of the filter.
+    pub fn hash_funcs(&self) -> u32 { self.hash_funcs }
+
+    /// Returns the tweak added to the seed of each hash function.
+    pub fn tweak(&self) -> u32 { self.tweak }
+
+    /// Returns the update flags of the filter.
+    pub fn flags(&self) -> BloomFlags { self.flags }
+
+    /// Returns the index of the bit set by hash function `n` for `data`.
+    fn hash(&self, n: u32, data: &[u8]) -> usize {
+        let seed = n.wrapping_mul(SEED_MULTIPLIER).wrapping_add(self.tweak);
+        murmur3(seed, data) as usize % (self.data.len() * 8)
+    }
+}
+
+impl From<&BloomFilter> for FilterLoad {
+    fn from(filter: &BloomFilter) -> Self {
+        FilterLoad {
+            filter: filter.data.clone(),
+            hash_funcs: filter.hash_funcs,
+            tweak: filter.tweak,
+            flags: filter.flags,
+        }
+    }
+}
+
+impl TryFrom<FilterLoad> for BloomFilter {
+    type Error = FilterLoadError;
+
+    fn try_from(msg: FilterLoad) -> Result<Self, Self::Error> {
+        if msg.filter.len() > MAX_BLOOM_FILTER_SIZE {
+            return Err(FilterLoadError::Oversize(msg.filter.len()));
+        }
+        if msg.hash_funcs > MAX_HASH_FUNCS {
+            return Err(FilterLoadError::TooManyHashFuncs(msg.hash_funcs));
+        }
+        Ok(BloomFilter {
+            data: msg.filter,
+            hash_funcs: msg.hash_funcs,
+            tweak: msg.tweak,
+            flags: msg.flags,
+        })
+    }
+}
+
+/// An error converting a [`FilterLoad`] message into a [`BloomFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterLoadError {
+    /// The filter is larger than [`MAX_BLOOM_FILTER_SIZE`] bytes (contains the size).
+    Oversize(usize),
+    /// The filter uses more than [`MAX_HASH_FUNCS`] hash functions (contains the number).
+    TooManyHashFuncs(u32),
+}
+
+impl fmt::Display for FilterLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use FilterLoadError::*;
+
+        match *self {
+            Oversize(size) => write!(
+                f,
+                "bloom filter of {} bytes exceeds the maximum of {}",
+                size, MAX_BLOOM_FILTER_SIZE
+            ),
+            TooManyHashFuncs(n) => write!(
+                f,
+                "bloom filter uses {} hash functions, the maximum is {}",
+                n, MAX_HASH_FUNCS
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FilterLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use FilterLoadError::*;
+
+        match *self {
+            Oversize(_) | TooManyHashFuncs(_) => None,
+        }
+    }
+}
+
+/// Computes the 32-bit MurmurHash3 (x86 variant) of `data`.
+fn murmur3(seed: u32, data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut h = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, byte) in tail.iter().enumerate() {
+            k ^= u32::from(*byte) << (8 * i);
+        }
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^ (h >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use hashes::{hash160, Hash};
+    use hex::test_hex_unwrap as hex;
+
+    use super::*;
+
+    #[test]
+    fn murmur3_vectors() {
+        // From Bitcoin Core's `hash_tests.cpp`.
+        let vectors: &[(u32, u32, &str)] = &[
+            (0x00000000, 0x00000000, ""),
+            (0x6a396f08, 0xFBA4C795, ""),
+            (0x81f16f39, 0xffffffff, ""),
+            (0x514e28b7, 0x00000000, "00"),
+            (0xea3f0b17, 0xFBA4C795, "00"),
+            (0xfd6cf10d, 0x00000000, "ff"),
+            (0x16c6b7ab, 0x00000000, "0011"),
+            (0x8eb51c3d, 0x00000000, "001122"),
+            (0xb4471bf8, 0x00000000, "00112233"),
+            (0xe2301fa8, 0x00000000, "0011223344"),
+            (0xb074502c, 0x00000000, "00112233445566"),
+        ];
+        for (expected, seed, data) in vectors {
+            assert_eq!(murmur3(*seed, &hex!(data)), *expected, "{}", data);
+        }
+    }
+
+    #[test]
+    fn create_insert_serialize() {
+        // From Bitcoin Core's `bloom_tests.cpp`.
+        let filter = BloomFilter::new(3, 0.01);
+        assert_eq!(filter.as_bytes(), &[0; 3]);
+        assert_eq!(filter.hash_funcs(), 5);
+
+        for (tweak, expected) in [(0, "614e9b"), (2147483649, "ce4299")].iter() {
+            let mut filter = BloomFilter::from_bytes(&[0; 3], 5, *tweak, BloomFlags::All);
+
+            filter.insert(&hex!("99108ad8ed9bb6274d3980bab5a85c048f0950c8"));
+            assert!(filter.contains(&hex!("99108ad8ed9bb6274d3980bab5a85c048f0950c8")));
+            // One bit different in first byte.
+            assert!(!filter.contains(&hex!("19108ad8ed9bb6274d3980bab5a85c048f0950c8")));
+
+            filter.insert(&hex!("b5a2c786d9ef4658287ced5914b37a1b4aa32eee"));
+            filter.insert(&hex!("b9300670b4c5366e95b2699e8b18bc75e5f729c5"));
+            assert!(filter.contains(&hex!("b5a2c786d9ef4658287ced5914b37a1b4aa32eee")));
+            assert!(filter.contains(&hex!("b9300670b4c5366e95b2699e8b18bc75e5f729c5")));
+            assert_eq!(filter.as_bytes(), &hex!(expected)[..]);
+            assert_eq!(filter.tweak(), *tweak);
+            assert_eq!(filter.flags(), BloomFlags::All);
+        }
+    }
+
+    #[test]
+    fn filter_load_roundtrip() {
+        let mut filter = BloomFilter::new(3, 0.01);
+        filter.insert(&hex!("99108ad8ed9bb6274d3980bab5a85c048f0950c8"));
+
+        let msg = FilterLoad::from(&filter);
+        assert_eq!(msg.filter, filter.as_bytes());
+        assert_eq!(msg.hash_funcs, filter.hash_funcs());
+        assert_eq!(msg.flags, BloomFlags::None);
+        assert_eq!(BloomFilter::try_from(msg.clone()), Ok(filter));
+
+        let oversize = FilterLoad { filter: vec![0; MAX_BLOOM_FILTER_SIZE + 1], ..msg.clone() };
+        assert_eq!(
+            BloomFilter::try_from(oversize),
+            Err(FilterLoadError::Oversize(MAX_BLOOM_FILTER_SIZE + 1))
+        );
+        let too_many = FilterLoad { hash_funcs: MAX_HASH_FUNCS + 1, ..msg };
+        assert_eq!(
+            BloomFilter::try_from(too_many),
+            Err(FilterLoadError::TooManyHashFuncs(MAX_HASH_FUNCS + 1))
+        );
+    }
+
+    #[test]
+    fn false_positive_rate() {
+        let address = |i: u32| hash160::Hash::hash(&i.to_le_bytes());
+        let fp_rate = 0.01;
+
+        let mut filter = BloomFilter::new(1000, fp_rate);
+        for i in 0..1000 {
+            filter.insert(address(i).as_byte_array());
+        }
+        for i in 0..1000 {
+            assert!(filter.contains(address(i).as_byte_array()));
+        }
+
+        let tries = 10_000;
+        let false_positives =
+            (1000..1000 + tries).filter(|i| filter.contains(address(*i).as_byte_array())).count();
+        assert!(false_positives as f64 <= 2.0 * fp_rate * f64::from(tries), "{}", false_positives);
+    }
+
+    #[test]
+    fn size_limits() {
+        let filter = BloomFilter::new(1_000_000, 0.000_001);
+        assert_eq!(filter.as_bytes().len(), MAX_BLOOM_FILTER_SIZE);
+        assert!(filter.hash_funcs() <= MAX_HASH_FUNCS);
+
+        let filter = BloomFilter::new(1, 0.000_000_000_000_000_001);
+        assert_eq!(filter.hash_funcs(), MAX_HASH_FUNCS);
+
+        // A filter too small to have any bit matches everything.
+        let mut filter = BloomFilter::new(1, 1.0);
+        assert!(filter.as_bytes().is_empty());
+        filter.insert(&[0x42]);
+        assert!(filter.contains(&[0x13]));
+    }
+}
@@ -10,6 +10,8 @@ pub mod address;
 #[cfg(feature = "std")]
 pub use self::address::Address;
 #[cfg(feature = "std")]
+pub mod bloom;
+#[cfg(feature = "std")]
 pub mod message;
 #[cfg(feature = "std")]
 pub mod message_blockdata;
@@ -22,6 +22,12 @@ pub const MAX_STANDARD_TX_WEIGHT: u32 = 400_000;
 /// Minimum non-witness size for a standard transaction (1 segwit input + 1 P2WPKH output = 82 bytes)
 pub const MIN_STANDARD_TX_NONWITNESS_SIZE: u32 = 82;
 
+/// Maximum size of a scriptSig in a standard tx, large enough for a 15-of-15 P2SH multisig spend.
+pub const MAX_STANDARD_SCRIPTSIG_SIZE: u32 = 1_650;
+
+/// Maximum size of an `OP_RETURN` scriptPubKey relayed by default, including the opcode.
+pub const MAX_OP_RETURN_RELAY: u32 = 83;
+
 /// Maximum number of sigops in a standard tx.
 pub const MAX_STANDARD_TX_SIGOPS_COST: u32 = MAX_BLOCK_SIGOPS_COST as u32 / 5;
 
@@ -6,17 +6,22 @@
 //! functions here are designed to be fast, by that we mean it is safe to use them to check headers.
 //!
 
+use core::cmp::Ordering;
 use core::fmt::{self, LowerHex, UpperHex};
-use core::ops::{Add, Div, Mul, Not, Rem, Shl, Shr, Sub};
+use core::ops::{Add, AddAssign, Div, Mul, Not, Rem, Shl, Shr, Sub};
 
 #[cfg(all(test, mutate))]
 use mutagen::mutate;
 
+use crate::blockdata::constants::{
+    DIFFCHANGE_TIMESPAN, MAINNET_MIN_DIFFICULTY, REGTEST_MIN_DIFFICULTY,
+};
 use crate::consensus::encode::{self, Decodable, Encodable};
 #[cfg(doc)]
 use crate::consensus::Params;
 use crate::hash_types::BlockHash;
 use crate::io::{self, Read, Write};
+use crate::network::Network;
 use crate::prelude::String;
 use crate::string::FromHexStr;
 
@@ -67,6 +72,15 @@ macro_rules! do_impl {
 pub struct Work(U256);
 
 impl Work {
+    /// No work, the starting point when accumulating chain work.
+    pub const ZERO: Work = Work(U256::ZERO);
+
+    /// The maximum representable amount of work.
+    pub const MAX: Work = Work(U256::MAX);
+
+    /// Computes the expected number of hashes needed to find a block with the given `bits`.
+    pub fn from_compact_target(bits: CompactTarget) -> Work { Target::from_compact(bits).to_work() }
+
     /// Converts this [`Work`] to [`Target`].
     pub fn to_target(self) -> Target { Target(self.0.inverse()) }
 
@@ -77,6 +91,19 @@ impl Work {
     /// `log2_work` output in its logs.
     #[cfg(feature = "std")]
     pub fn log2(self) -> f64 { self.0.to_f64().log2() }
+
+    /// Adds `rhs` to this work, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Work) -> Option<Work> {
+        match self.0.overflowing_add(rhs.0) {
+            (sum, false) => Some(Work(sum)),
+            (_, true) => None,
+        }
+    }
+
+    /// Compares the work of two chains, the chain with more work is the better one.
+    ///
+    /// This is the same as [`Ord::cmp`], spelled out for chain selection code.
+    pub fn cmp_chain(self, other: Work) -> Ordering { self.cmp(&other) }
 }
 do_impl!(Work);
 
@@ -85,6 +112,10 @@ impl Add for Work {
     fn add(self, rhs: Self) -> Self { Work(self.0 + rhs.0) }
 }
 
+impl AddAssign for Work {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs }
+}
+
 impl Sub for Work {
     type Output = Work;
     fn sub(self, rhs: Self) -> Self { Work(self.0 - rhs.0) }
@@ -255,6 +286,31 @@ impl Target {
     /// The difficulty can only decrease or increase by a factor of 4 max on each difficulty
     /// adjustment period.
     pub fn max_difficulty_transition_threshold(&self) -> Self { Self(self.0 << 2) }
+
+    /// Computes the target of the next difficulty adjustment period from this, the target of the
+    /// last period, and the number of seconds the last period actually took.
+    ///
+    /// `actual_timespan` is clamped to within a factor of 4 of [`DIFFCHANGE_TIMESPAN`] and the
+    /// result never exceeds the Lebowkis minimum difficulty ([`MAINNET_MIN_DIFFICULTY`]).
+    ///
+    /// [`DIFFCHANGE_TIMESPAN`]: crate::blockdata::constants::DIFFCHANGE_TIMESPAN
+    /// [`MAINNET_MIN_DIFFICULTY`]: crate::blockdata::constants::MAINNET_MIN_DIFFICULTY
+    pub fn retarget(self, actual_timespan: u64) -> Target {
+        let timespan = u64::from(DIFFCHANGE_TIMESPAN);
+        let actual = actual_timespan.max(timespan / 4).min(timespan * 4);
+        let limit = Target::from_compact(MAINNET_MIN_DIFFICULTY);
+
+        let (scaled, overflow) = self.0.mul_u64(actual);
+        if overflow {
+            return limit;
+        }
+        let next = Target(scaled / U256::from(timespan));
+        if next > limit {
+            limit
+        } else {
+            next
+        }
+    }
 }
 do_impl!(Target);
 
@@ -272,10 +328,25 @@ pub struct CompactTarget(u32);
 
 impl CompactTarget {
     /// Creates a [`CompactTarget`] from a consensus encoded `u32`.
-    pub fn from_consensus(bits: u32) -> Self { Self(bits) }
+    pub const fn from_consensus(bits: u32) -> Self { Self(bits) }
 
     /// Returns the consensus encoded `u32` representation of this [`CompactTarget`].
-    pub fn to_consensus(self) -> u32 { self.0 }
+    pub const fn to_consensus(self) -> u32 { self.0 }
+
+    /// Returns true if this is the minimum difficulty (i.e. the easiest target) of `network`.
+    ///
+    /// A block whose target is easier than this is invalid, see
+    /// [`MAINNET_MIN_DIFFICULTY`] and [`REGTEST_MIN_DIFFICULTY`].
+    ///
+    /// [`MAINNET_MIN_DIFFICULTY`]: crate::blockdata::constants::MAINNET_MIN_DIFFICULTY
+    /// [`REGTEST_MIN_DIFFICULTY`]: crate::blockdata::constants::REGTEST_MIN_DIFFICULTY
+    pub fn is_at_minimum_difficulty(self, network: Network) -> bool {
+        let minimum = match network {
+            Network::Bitcoin | Network::Testnet | Network::Signet => MAINNET_MIN_DIFFICULTY,
+            Network::Regtest => REGTEST_MIN_DIFFICULTY,
+        };
+        self == minimum
+    }
 }
 
 impl From<CompactTarget> for Target {
@@ -1698,6 +1769,33 @@ mod tests {
     #[should_panic]
     fn work_overflowing_subtraction_panics() { let _ = Work(U256::ZERO) - Work(U256::ONE); }
 
+    #[test]
+    fn work_from_compact_target() {
+        let easy = Work::from_compact_target(CompactTarget::from_consensus(0x1e0ffff0));
+        let hard = Work::from_compact_target(CompactTarget::from_consensus(0x1b0404cb));
+        assert!(Work::ZERO < easy);
+        assert!(easy < hard);
+
+        let mut total = Work::ZERO;
+        total += easy;
+        total += hard;
+        assert_eq!(total, easy + hard);
+        assert!(total > hard);
+    }
+
+    #[test]
+    fn work_checked_add() {
+        let easy = Work::from_compact_target(CompactTarget::from_consensus(0x1e0ffff0));
+        assert_eq!(Work::ZERO.checked_add(easy), Some(easy));
+        assert_eq!(easy.checked_add(easy), Some(easy + easy));
+        assert_eq!(Work::MAX.checked_add(Work(U256::ONE)), None);
+        assert_eq!(Work::MAX.checked_add(Work::ZERO), Some(Work::MAX));
+
+        assert_eq!(easy.cmp_chain(easy + easy), Ordering::Less);
+        assert_eq!(easy.cmp_chain(easy), Ordering::Equal);
+        assert_eq!(Work::MAX.cmp_chain(easy), Ordering::Greater);
+    }
+
     #[test]
     fn u256_to_f64() {
         // Validate that the Target::MAX value matches the constant also used in difficulty calculation.
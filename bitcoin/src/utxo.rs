@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Unspent transaction output set.
+//!
+//! This module provides [`UtxoSet`], a simple in-memory set of unspent transaction outputs that
+//! is updated by applying blocks in chain order, as needed by an indexer processing blocks.
+//!
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::blockdata::block::Block;
+use crate::blockdata::transaction::{OutPoint, TxOut};
+
+/// An in-memory set of unspent transaction outputs.
+///
+/// Provably unspendable outputs (e.g. `OP_RETURN`) are never added to the set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UtxoSet {
+    utxos: HashMap<OutPoint, TxOut>,
+    height: Option<u32>,
+}
+
+impl UtxoSet {
+    /// Creates an empty UTXO set.
+    pub fn new() -> Self { UtxoSet::default() }
+
+    /// Applies `block` at `height`, removing the outputs spent by the block and adding the ones
+    /// it creates.
+    ///
+    /// Inputs may spend outputs created earlier in the same block. Coinbase inputs are skipped.
+    /// If an input spends an output not in the set an error is returned and the set is left as it
+    /// was before the call.
+    pub fn apply_block(&mut self, block: &Block, height: u32) -> Result<(), UtxoError> {
+        let mut undo = Vec::new();
+
+        for tx in &block.txdata {
+            if !tx.is_coinbase() {
+                for input in &tx.input {
+                    match self.utxos.remove(&input.previous_output) {
+                        Some(txout) => undo.push(Undo::Spent(input.previous_output, txout)),
+                        None => {
+                            self.revert(undo);
+                            return Err(UtxoError::SpentOutput(input.previous_output));
+                        }
+                    }
+                }
+            }
+
+            let txid = tx.txid();
+            for (vout, txout) in tx.output.iter().enumerate() {
+                if txout.script_pubkey.is_provably_unspendable() {
+                    continue;
+                }
+                let outpoint = OutPoint::new(txid, vout as u32);
+                // A duplicate transaction (BIP30) replaces an output, restore it on revert.
+                let replaced = self.utxos.insert(outpoint, txout.clone());
+                undo.push(Undo::Added(outpoint, replaced));
+            }
+        }
+
+        self.height = Some(height);
+        Ok(())
+    }
+
+    /// Undoes a partially applied block, in the reverse order of the changes.
+    fn revert(&mut self, undo: Vec<Undo>) {
+        for change in undo.into_iter().rev() {
+            match change {
+                Undo::Spent(outpoint, txout) => {
+                    self.utxos.insert(outpoint, txout);
+                }
+                Undo::Added(outpoint, replaced) => {
+                    self.utxos.remove(&outpoint);
+                    if let Some(txout) = replaced {
+                        self.utxos.insert(outpoint, txout);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the unspent output at `outpoint`, if any.
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&TxOut> { self.utxos.get(outpoint) }
+
+    /// Returns true if the output at `outpoint` is unspent.
+    pub fn contains(&self, outpoint: &OutPoint) -> bool { self.utxos.contains_key(outpoint) }
+
+    /// Returns the number of unspent outputs.
+    pub fn len(&self) -> usize { self.utxos.len() }
+
+    /// Returns true if there are no unspent outputs.
+    pub fn is_empty(&self) -> bool { self.utxos.is_empty() }
+
+    /// Returns the height of the last block applied, if any.
+    pub fn height(&self) -> Option<u32> { self.height }
+}
+
+/// A change made to a [`UtxoSet`] while applying a block.
+#[derive(Debug)]
+enum Undo {
+    /// An output was spent.
+    Spent(OutPoint, TxOut),
+    /// An output was added, replacing the given one if any.
+    Added(OutPoint, Option<TxOut>),
+}
+
+/// An error applying a block to a [`UtxoSet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UtxoError {
+    /// An input spends an output that is not in the set.
+    SpentOutput(OutPoint),
+}
+
+impl fmt::Display for UtxoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use UtxoError::*;
+
+        match *self {
+            SpentOutput(ref outpoint) => write!(f, "input spends unknown output {}", outpoint),
+        }
+    }
+}
+
+impl std::error::Error for UtxoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use UtxoError::*;
+
+        match *self {
+            SpentOutput(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashes::Hash;
+
+    use super::*;
+    use crate::blockdata::constants::genesis_block;
+    use crate::blockdata::locktime;
+    use crate::blockdata::script::ScriptBuf;
+    use crate::blockdata::transaction::{Transaction, TxIn, Version};
+    use crate::crypto::key::PubkeyHash;
+    use crate::hash_types::Txid;
+    use crate::network::Network;
+    use crate::Amount;
+
+    fn spend(outpoints: &[OutPoint], values: &[u64]) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: locktime::absolute::LockTime::ZERO,
+            input: outpoints
+                .iter()
+                .map(|outpoint| TxIn { previous_output: *outpoint, ..Default::default() })
+                .collect(),
+            output: values
+                .iter()
+                .map(|value| TxOut {
+                    value: Amount::from_sat(*value),
+                    script_pubkey: ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+                })
+                .collect(),
+        }
+    }
+
+    fn block_with(txdata: Vec<Transaction>) -> Block {
+        Block { header: genesis_block(Network::Bitcoin).header, txdata }
+    }
+
+    #[test]
+    fn apply_genesis() {
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase = OutPoint::new(genesis.txdata[0].txid(), 0);
+
+        let mut utxos = UtxoSet::new();
+        assert!(utxos.is_empty());
+        utxos.apply_block(&genesis, 0).unwrap();
+        assert_eq!(utxos.len(), 1);
+        assert!(utxos.contains(&coinbase));
+        assert_eq!(utxos.get(&coinbase), Some(&genesis.txdata[0].output[0]));
+        assert_eq!(utxos.height(), Some(0));
+    }
+
+    #[test]
+    fn apply_spends() {
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase = OutPoint::new(genesis.txdata[0].txid(), 0);
+        let mut utxos = UtxoSet::new();
+        utxos.apply_block(&genesis, 0).unwrap();
+
+        // The second transaction spends an output of the first one in the same block.
+        let first = spend(&[coinbase], &[1_000, 2_000]);
+        let second = spend(&[OutPoint::new(first.txid(), 1)], &[1_500]);
+        let mut unspendable = spend(&[OutPoint::new(first.txid(), 0)], &[0]);
+        unspendable.output[0].script_pubkey = ScriptBuf::new_op_return(&[0x01; 4]);
+        let block = block_with(vec![first.clone(), second.clone(), unspendable]);
+
+        utxos.apply_block(&block, 1).unwrap();
+        assert!(!utxos.contains(&coinbase));
+        assert!(!utxos.contains(&OutPoint::new(first.txid(), 0)));
+        assert!(!utxos.contains(&OutPoint::new(first.txid(), 1)));
+        assert_eq!(utxos.get(&OutPoint::new(second.txid(), 0)).unwrap().value.to_sat(), 1_500);
+        assert_eq!(utxos.len(), 1);
+        assert_eq!(utxos.height(), Some(1));
+    }
+
+    #[test]
+    fn apply_unknown_spend() {
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase = OutPoint::new(genesis.txdata[0].txid(), 0);
+        let mut utxos = UtxoSet::new();
+        utxos.apply_block(&genesis, 0).unwrap();
+        let before = utxos.clone();
+
+        let unknown = OutPoint::new(Txid::all_zeros(), 7);
+        let first = spend(&[coinbase], &[1_000]);
+        let second = spend(&[unknown], &[500]);
+        let block = block_with(vec![first, second]);
+
+        assert_eq!(utxos.apply_block(&block, 1), Err(UtxoError::SpentOutput(unknown)));
+        assert_eq!(utxos, before);
+    }
+
+    #[test]
+    fn apply_unknown_spend_after_intra_block_spend() {
+        let genesis = genesis_block(Network::Bitcoin);
+        let coinbase = OutPoint::new(genesis.txdata[0].txid(), 0);
+        let mut utxos = UtxoSet::new();
+        utxos.apply_block(&genesis, 0).unwrap();
+        let before = utxos.clone();
+
+        // The second transaction spends an output created by the first, then the third fails.
+        let unknown = OutPoint::new(Txid::all_zeros(), 7);
+        let first = spend(&[coinbase], &[1_000]);
+        let second = spend(&[OutPoint::new(first.txid(), 0)], &[900]);
+        let third = spend(&[unknown], &[500]);
+        let block = block_with(vec![first, second, third]);
+
+        assert_eq!(utxos.apply_block(&block, 1), Err(UtxoError::SpentOutput(unknown)));
+        assert_eq!(utxos, before);
+        assert_eq!(utxos.len(), 1);
+    }
+}